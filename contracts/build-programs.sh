@@ -0,0 +1,39 @@
+#!/usr/bin/env bash
+# Build the router and every facet program (see src/facets), shrink each with wasm-opt and
+# check it against the Stylus size limit. Optimized programs are written to target/programs.
+#
+# Usage: ./build-programs.sh [program...]    (default: all programs)
+# Needs wasm-opt (binaryen) and cargo-stylus on PATH. SKIP_CHECK=1 skips `cargo stylus check`,
+# which needs an RPC endpoint (RPC_URL, default Arbitrum Sepolia).
+set -euo pipefail
+cd "$(dirname "$0")"
+
+PROGRAMS="router batch-publishing batch-unlocks catalog direct-unlocks escrow imports
+member-unlocks membership metadata migration payments pricing profiles publishing signatures
+unlocks vouchers"
+
+# Features rustc enables by default for wasm32-unknown-unknown, plus the ones in .cargo/config.toml
+WASM_FEATURES="--enable-bulk-memory --enable-sign-ext --enable-mutable-globals
+--enable-nontrapping-float-to-int --enable-reference-types --enable-multivalue"
+
+RPC_URL=${RPC_URL:-https://sepolia-rollup.arbitrum.io/rpc}
+OUT=target/programs
+mkdir -p "$OUT"
+
+for program in ${@:-$PROGRAMS}; do
+    features=""
+    if [ "$program" != router ]; then
+        features="--features $program"
+    fi
+
+    # One target dir per program, so switching features does not rebuild the others
+    cargo build --release --target wasm32-unknown-unknown $features --target-dir "target/$program"
+    wasm-opt -Oz $WASM_FEATURES \
+        "target/$program/wasm32-unknown-unknown/release/wikipay_contracts.wasm" \
+        -o "$OUT/$program.wasm"
+
+    if [ -z "${SKIP_CHECK:-}" ]; then
+        echo "== $program"
+        cargo stylus check --wasm-file "$OUT/$program.wasm" --endpoint "$RPC_URL"
+    fi
+done
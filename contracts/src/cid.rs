@@ -0,0 +1,604 @@
+//! IPFS content identifier (CID) parsing, validation and encoding
+//!
+//! Decodes CIDv0 (base58btc, `Qm...`) and CIDv1 in base32 (`b...`) or base36 (`k...`)
+//! multibase, accepting only the codecs and hash functions WikiPay content is stored with.
+//! A parsed CID packs into a 32-byte digest plus a 32-bit format word for compact storage,
+//! and encodes back to the exact string it was parsed from.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+/// Multicodec content types accepted for CIDv1
+pub const CODEC_RAW: u64 = 0x55;
+pub const CODEC_DAG_PB: u64 = 0x70;
+pub const CODEC_DAG_CBOR: u64 = 0x71;
+pub const CODEC_DAG_JSON: u64 = 0x0129;
+
+/// Multihash functions accepted for the content digest
+pub const HASH_SHA2_256: u64 = 0x12;
+pub const HASH_BLAKE3: u64 = 0x1e;
+
+/// Digest length of every accepted hash function
+pub const DIGEST_LENGTH: usize = 32;
+
+/// Longest CID string accepted, in bytes (bounds decoding cost)
+const MAX_CID_LENGTH: usize = 128;
+
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+const BASE32_ALPHABET: &[u8; 32] = b"abcdefghijklmnopqrstuvwxyz234567";
+const BASE36_ALPHABET: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// Multibase encoding of a CID string
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Multibase {
+    /// Bare base58btc (CIDv0)
+    Base58Btc = 0,
+    /// Lowercase RFC 4648 base32, prefix `b`
+    Base32 = 1,
+    /// Lowercase base36, prefix `k`
+    Base36 = 2,
+}
+
+/// Reasons a CID string is rejected
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CidError {
+    /// Empty string or longer than the accepted maximum
+    InvalidLength,
+    /// Multibase prefix other than base32 or base36 for CIDv1
+    UnsupportedMultibase,
+    /// Character outside the multibase alphabet, or non-canonical padding bits
+    InvalidEncoding,
+    /// Malformed or non-minimal varint
+    InvalidVarint,
+    /// CID version other than 0 or 1
+    UnsupportedVersion,
+    /// Multicodec content type not accepted
+    UnsupportedCodec,
+    /// Multihash function not accepted
+    UnsupportedHash,
+    /// Digest length does not match the hash function
+    InvalidDigestLength,
+    /// Bytes left over after the multihash digest
+    TrailingBytes,
+}
+
+impl CidError {
+    /// Revert reason for this error
+    pub fn message(&self) -> &'static str {
+        match self {
+            CidError::InvalidLength => "Invalid IPFS hash length",
+            CidError::UnsupportedMultibase => "Unsupported CID multibase",
+            CidError::InvalidEncoding => "Invalid CID encoding",
+            CidError::InvalidVarint => "Invalid CID varint",
+            CidError::UnsupportedVersion => "Unsupported CID version",
+            CidError::UnsupportedCodec => "Unsupported CID codec",
+            CidError::UnsupportedHash => "Unsupported CID hash function",
+            CidError::InvalidDigestLength => "Invalid CID digest length",
+            CidError::TrailingBytes => "Trailing bytes after CID digest",
+        }
+    }
+}
+
+impl fmt::Display for CidError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.message())
+    }
+}
+
+/// Decoded CID
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cid {
+    /// CID version (0 or 1)
+    pub version: u8,
+    /// Multibase the CID string was written in
+    pub base: Multibase,
+    /// Multicodec content type (always dag-pb for CIDv0)
+    pub codec: u64,
+    /// Multihash function code
+    pub hash: u64,
+    /// Content digest
+    pub digest: [u8; DIGEST_LENGTH],
+}
+
+impl Cid {
+    /// Parse and validate a CID string
+    pub fn parse(input: &str) -> Result<Self, CidError> {
+        let input = input.as_bytes();
+        if input.is_empty() || input.len() > MAX_CID_LENGTH {
+            return Err(CidError::InvalidLength);
+        }
+
+        // CIDv0: bare base58btc sha2-256 multihash, always 46 characters starting with "Qm"
+        if input.len() == 46 && input.starts_with(b"Qm") {
+            let bytes = decode_base58(input)?;
+            let (cid, rest) =
+                Self::decode_multihash(0, Multibase::Base58Btc, CODEC_DAG_PB, &bytes)?;
+            if !rest.is_empty() {
+                return Err(CidError::TrailingBytes);
+            }
+            if cid.hash != HASH_SHA2_256 {
+                return Err(CidError::UnsupportedHash);
+            }
+            return Ok(cid);
+        }
+
+        let (base, bytes) = match input[0] {
+            b'b' => (Multibase::Base32, decode_base32(&input[1..])?),
+            b'k' => (Multibase::Base36, decode_base36(&input[1..])?),
+            _ => return Err(CidError::UnsupportedMultibase),
+        };
+
+        let (version, rest) = read_varint(&bytes)?;
+        if version != 1 {
+            return Err(CidError::UnsupportedVersion);
+        }
+
+        let (codec, rest) = read_varint(rest)?;
+        if !matches!(
+            codec,
+            CODEC_RAW | CODEC_DAG_PB | CODEC_DAG_CBOR | CODEC_DAG_JSON
+        ) {
+            return Err(CidError::UnsupportedCodec);
+        }
+
+        let (cid, rest) = Self::decode_multihash(1, base, codec, rest)?;
+        if !rest.is_empty() {
+            return Err(CidError::TrailingBytes);
+        }
+
+        Ok(cid)
+    }
+
+    /// Parse a CID that may be left empty, as in profile fields
+    /// Returns `None` for the empty string and rejects anything else `parse` rejects
+    pub fn parse_optional(input: &str) -> Result<Option<Self>, CidError> {
+        if input.is_empty() {
+            return Ok(None);
+        }
+        Self::parse(input).map(Some)
+    }
+
+    /// Pack version, multibase, hash function and codec into one word
+    /// Layout: version (bits 28-31), multibase (24-27), hash (16-23), codec (0-15);
+    /// never zero for a valid CID, so zero can mark an absent CID in storage
+    pub fn format(&self) -> u32 {
+        (u32::from(self.version) << 28)
+            | ((self.base as u32) << 24)
+            | ((self.hash as u32) << 16)
+            | self.codec as u32
+    }
+
+    /// Rebuild a CID from its packed format word and digest
+    /// Returns `None` for words that `format` never produces
+    pub fn from_format(format: u32, digest: [u8; DIGEST_LENGTH]) -> Option<Self> {
+        let version = (format >> 28) as u8;
+        let base = match (format >> 24) & 0xf {
+            0 => Multibase::Base58Btc,
+            1 => Multibase::Base32,
+            2 => Multibase::Base36,
+            _ => return None,
+        };
+        let hash = u64::from((format >> 16) & 0xff);
+        let codec = u64::from(format & 0xffff);
+
+        let valid = match version {
+            0 => base == Multibase::Base58Btc && codec == CODEC_DAG_PB && hash == HASH_SHA2_256,
+            1 => {
+                base != Multibase::Base58Btc
+                    && matches!(
+                        codec,
+                        CODEC_RAW | CODEC_DAG_PB | CODEC_DAG_CBOR | CODEC_DAG_JSON
+                    )
+                    && matches!(hash, HASH_SHA2_256 | HASH_BLAKE3)
+            }
+            _ => false,
+        };
+
+        valid.then_some(Cid {
+            version,
+            base,
+            codec,
+            hash,
+            digest,
+        })
+    }
+
+    /// Binary CID: the bare multihash for CIDv0, `<version><codec><multihash>` for CIDv1
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(DIGEST_LENGTH + 6);
+        if self.version == 1 {
+            write_varint(&mut bytes, 1);
+            write_varint(&mut bytes, self.codec);
+        }
+        write_varint(&mut bytes, self.hash);
+        write_varint(&mut bytes, DIGEST_LENGTH as u64);
+        bytes.extend_from_slice(&self.digest);
+        bytes
+    }
+
+    /// Encode the CID as a string in its multibase
+    pub fn encode(&self) -> String {
+        let bytes = self.to_bytes();
+        let mut out = String::with_capacity(64);
+
+        match self.base {
+            Multibase::Base58Btc => encode_radix(&mut out, &bytes, BASE58_ALPHABET),
+            Multibase::Base32 => {
+                out.push('b');
+                encode_base32(&mut out, &bytes);
+            }
+            Multibase::Base36 => {
+                out.push('k');
+                encode_radix(&mut out, &bytes, BASE36_ALPHABET);
+            }
+        }
+
+        out
+    }
+
+    /// Decode `<hash code><digest length><digest>`, returning the remaining bytes
+    fn decode_multihash(
+        version: u8,
+        base: Multibase,
+        codec: u64,
+        bytes: &[u8],
+    ) -> Result<(Self, &[u8]), CidError> {
+        let (hash, rest) = read_varint(bytes)?;
+        if !matches!(hash, HASH_SHA2_256 | HASH_BLAKE3) {
+            return Err(CidError::UnsupportedHash);
+        }
+
+        let (length, rest) = read_varint(rest)?;
+        if length != DIGEST_LENGTH as u64 || rest.len() < DIGEST_LENGTH {
+            return Err(CidError::InvalidDigestLength);
+        }
+
+        let mut digest = [0u8; DIGEST_LENGTH];
+        digest.copy_from_slice(&rest[..DIGEST_LENGTH]);
+
+        let cid = Cid {
+            version,
+            base,
+            codec,
+            hash,
+            digest,
+        };
+        Ok((cid, &rest[DIGEST_LENGTH..]))
+    }
+}
+
+/// Read an unsigned LEB128 varint (multiformats limit it to 9 bytes and minimal encoding)
+fn read_varint(bytes: &[u8]) -> Result<(u64, &[u8]), CidError> {
+    let mut value = 0u64;
+
+    for (i, &byte) in bytes.iter().enumerate().take(9) {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            // A trailing zero byte means the varint was not minimally encoded
+            if byte == 0 && i > 0 {
+                return Err(CidError::InvalidVarint);
+            }
+            return Ok((value, &bytes[i + 1..]));
+        }
+    }
+
+    Err(CidError::InvalidVarint)
+}
+
+/// Append an unsigned LEB128 varint
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Position of a character in a multibase alphabet
+fn alphabet_index(alphabet: &[u8], c: u8) -> Result<u32, CidError> {
+    alphabet
+        .iter()
+        .position(|&a| a == c)
+        .map(|i| i as u32)
+        .ok_or(CidError::InvalidEncoding)
+}
+
+/// Decode a big-endian number written in `alphabet`, where each leading zero digit
+/// stands for one leading zero byte (base58btc and base36 multibase)
+fn decode_radix(input: &[u8], alphabet: &[u8]) -> Result<Vec<u8>, CidError> {
+    let radix = alphabet.len() as u32;
+    let zero = alphabet[0];
+    let leading_zeros = input.iter().take_while(|&&c| c == zero).count();
+
+    // Little-endian base-256 accumulator
+    let mut number: Vec<u8> = Vec::with_capacity(input.len());
+    for &c in &input[leading_zeros..] {
+        let mut carry = alphabet_index(alphabet, c)?;
+        for byte in number.iter_mut() {
+            carry += u32::from(*byte) * radix;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            number.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+
+    let mut bytes = vec![0u8; leading_zeros];
+    bytes.extend(number.iter().rev());
+    Ok(bytes)
+}
+
+/// Encode bytes as a big-endian number in `alphabet`, writing one zero digit per
+/// leading zero byte (inverse of `decode_radix`)
+fn encode_radix(out: &mut String, bytes: &[u8], alphabet: &[u8]) {
+    let radix = alphabet.len() as u32;
+    let leading_zeros = bytes.iter().take_while(|&&b| b == 0).count();
+
+    // Little-endian digit accumulator
+    let mut digits: Vec<u8> = Vec::with_capacity(bytes.len() * 2);
+    for &byte in &bytes[leading_zeros..] {
+        let mut carry = u32::from(byte);
+        for digit in digits.iter_mut() {
+            carry += u32::from(*digit) << 8;
+            *digit = (carry % radix) as u8;
+            carry /= radix;
+        }
+        while carry > 0 {
+            digits.push((carry % radix) as u8);
+            carry /= radix;
+        }
+    }
+
+    for _ in 0..leading_zeros {
+        out.push(alphabet[0] as char);
+    }
+    for &digit in digits.iter().rev() {
+        out.push(alphabet[digit as usize] as char);
+    }
+}
+
+/// Encode RFC 4648 lowercase base32 without padding
+fn encode_base32(out: &mut String, bytes: &[u8]) {
+    let mut buffer = 0u32;
+    let mut bits = 0u32;
+
+    for &byte in bytes {
+        buffer = (buffer << 8) | u32::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+        buffer &= (1 << bits) - 1;
+    }
+
+    if bits > 0 {
+        out.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+}
+
+fn decode_base58(input: &[u8]) -> Result<Vec<u8>, CidError> {
+    decode_radix(input, BASE58_ALPHABET)
+}
+
+fn decode_base36(input: &[u8]) -> Result<Vec<u8>, CidError> {
+    decode_radix(input, BASE36_ALPHABET)
+}
+
+/// Decode RFC 4648 lowercase base32 without padding
+fn decode_base32(input: &[u8]) -> Result<Vec<u8>, CidError> {
+    let mut bytes = Vec::with_capacity(input.len() * 5 / 8);
+    let mut buffer = 0u32;
+    let mut bits = 0u32;
+
+    for &c in input {
+        buffer = (buffer << 5) | alphabet_index(BASE32_ALPHABET, c)?;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    // Leftover bits are padding: fewer than a full character, and all zero
+    if bits >= 5 || buffer != 0 {
+        return Err(CidError::InvalidEncoding);
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const V0: &str = "QmYwAPJzv5CZsnA625s3Xf2nemtYgPpHdWEz79ojWnPbdG";
+    const V1_DAG_PB: &str = "bafybeie5nqv6kd3qnfjupgvz34woh3oksc3iau6abmyajn7qvtf6d2ho34";
+    const V1_BASE36: &str = "k2jmtxvacy5p64u708sn9oawhfsizpcwgk1g59ckse0h1r7a2j7d0tlr";
+    /// sha2-256("hello world") as a raw-codec CIDv1
+    const V1_RAW: &str = "bafkreifzjut3te2nhyekklss27nh3k72ysco7y32koao5eei66wof36n5e";
+
+    fn digest(hex: &str) -> [u8; DIGEST_LENGTH] {
+        let mut out = [0u8; DIGEST_LENGTH];
+        for (i, byte) in out.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap();
+        }
+        out
+    }
+
+    fn base32(bytes: &[u8]) -> String {
+        let mut out = String::from("b");
+        encode_base32(&mut out, bytes);
+        out
+    }
+
+    fn assert_round_trip(input: &str) -> Cid {
+        let cid = Cid::parse(input).unwrap();
+        assert_eq!(cid.encode(), input);
+        assert_eq!(Cid::from_format(cid.format(), cid.digest), Some(cid));
+        cid
+    }
+
+    #[test]
+    fn round_trips_cid_v0() {
+        let cid = assert_round_trip(V0);
+        assert_eq!(cid.version, 0);
+        assert_eq!(cid.base, Multibase::Base58Btc);
+        assert_eq!(cid.codec, CODEC_DAG_PB);
+        assert_eq!(cid.hash, HASH_SHA2_256);
+        assert_eq!(
+            cid.digest,
+            digest("9d6c2be50f706953479ab9df2ce3edca90b68053c00b3004b7f0accbe1e8eedf")
+        );
+    }
+
+    #[test]
+    fn round_trips_base32_dag_pb() {
+        let v0 = Cid::parse(V0).unwrap();
+        let cid = assert_round_trip(V1_DAG_PB);
+        assert_eq!(cid.version, 1);
+        assert_eq!(cid.base, Multibase::Base32);
+        assert_eq!(cid.codec, CODEC_DAG_PB);
+        assert_eq!(cid.digest, v0.digest);
+    }
+
+    #[test]
+    fn round_trips_base32_raw() {
+        let cid = assert_round_trip(V1_RAW);
+        assert_eq!(cid.codec, CODEC_RAW);
+        assert_eq!(cid.hash, HASH_SHA2_256);
+        assert_eq!(
+            cid.digest,
+            digest("b94d27b9934d3e08a52e52d7da7dabfac484efe37a5380ee9088f7ace2efcde9")
+        );
+    }
+
+    #[test]
+    fn round_trips_base36() {
+        let v0 = Cid::parse(V0).unwrap();
+        let cid = assert_round_trip(V1_BASE36);
+        assert_eq!(cid.version, 1);
+        assert_eq!(cid.base, Multibase::Base36);
+        assert_eq!(cid.codec, CODEC_DAG_PB);
+        assert_eq!(cid.digest, v0.digest);
+    }
+
+    #[test]
+    fn round_trips_every_accepted_codec_and_hash() {
+        for base in [Multibase::Base32, Multibase::Base36] {
+            for codec in [CODEC_RAW, CODEC_DAG_PB, CODEC_DAG_CBOR, CODEC_DAG_JSON] {
+                for hash in [HASH_SHA2_256, HASH_BLAKE3] {
+                    let cid = Cid {
+                        version: 1,
+                        base,
+                        codec,
+                        hash,
+                        digest: [0xa5; DIGEST_LENGTH],
+                    };
+                    assert_eq!(Cid::parse(&cid.encode()), Ok(cid));
+                    assert_eq!(Cid::from_format(cid.format(), cid.digest), Some(cid));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_non_canonical_base32_padding() {
+        // The last character carries two padding bits; 'f' sets one of them
+        let mut input = String::from(V1_RAW);
+        input.pop();
+        input.push('f');
+        assert_eq!(Cid::parse(&input), Err(CidError::InvalidEncoding));
+    }
+
+    #[test]
+    fn rejects_non_minimal_varints() {
+        let mut bytes = vec![0x01, 0xf0, 0x00, 0x12, 0x20];
+        bytes.extend_from_slice(&[0u8; DIGEST_LENGTH]);
+        assert_eq!(Cid::parse(&base32(&bytes)), Err(CidError::InvalidVarint));
+
+        let mut bytes = vec![0x81, 0x00, 0x70, 0x12, 0x20];
+        bytes.extend_from_slice(&[0u8; DIGEST_LENGTH]);
+        assert_eq!(Cid::parse(&base32(&bytes)), Err(CidError::InvalidVarint));
+    }
+
+    #[test]
+    fn rejects_wrong_digest_length() {
+        let mut bytes = vec![0x01, 0x55, 0x12, 0x1f];
+        bytes.extend_from_slice(&[0u8; DIGEST_LENGTH - 1]);
+        assert_eq!(
+            Cid::parse(&base32(&bytes)),
+            Err(CidError::InvalidDigestLength)
+        );
+
+        let mut bytes = vec![0x01, 0x55, 0x12, 0x20];
+        bytes.extend_from_slice(&[0u8; DIGEST_LENGTH - 1]);
+        assert_eq!(
+            Cid::parse(&base32(&bytes)),
+            Err(CidError::InvalidDigestLength)
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_codec() {
+        // libp2p-key (0x72) is a valid multicodec but not article content
+        let mut bytes = vec![0x01, 0x72, 0x12, 0x20];
+        bytes.extend_from_slice(&[0u8; DIGEST_LENGTH]);
+        assert_eq!(Cid::parse(&base32(&bytes)), Err(CidError::UnsupportedCodec));
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert_eq!(Cid::parse(""), Err(CidError::InvalidLength));
+        assert_eq!(Cid::parse("zQm"), Err(CidError::UnsupportedMultibase));
+        assert_eq!(Cid::parse("bafyBEIE"), Err(CidError::InvalidEncoding));
+
+        let mut bytes = vec![0x02, 0x70, 0x12, 0x20];
+        bytes.extend_from_slice(&[0u8; DIGEST_LENGTH]);
+        assert_eq!(
+            Cid::parse(&base32(&bytes)),
+            Err(CidError::UnsupportedVersion)
+        );
+
+        let mut bytes = vec![0x01, 0x70, 0x11, 0x20];
+        bytes.extend_from_slice(&[0u8; DIGEST_LENGTH]);
+        assert_eq!(Cid::parse(&base32(&bytes)), Err(CidError::UnsupportedHash));
+
+        let mut bytes = vec![0x01, 0x70, 0x12, 0x20];
+        bytes.extend_from_slice(&[0u8; DIGEST_LENGTH + 1]);
+        assert_eq!(Cid::parse(&base32(&bytes)), Err(CidError::TrailingBytes));
+    }
+
+    #[test]
+    fn optional_cid_allows_empty_and_rejects_bad_input() {
+        assert_eq!(Cid::parse_optional(""), Ok(None));
+        assert_eq!(Cid::parse_optional(V0), Ok(Some(Cid::parse(V0).unwrap())));
+        assert_eq!(
+            Cid::parse_optional("https://example.com/avatar.png"),
+            Err(CidError::UnsupportedMultibase)
+        );
+        assert_eq!(
+            Cid::parse_optional(&"b".repeat(MAX_CID_LENGTH + 1)),
+            Err(CidError::InvalidLength)
+        );
+    }
+
+    #[test]
+    fn from_format_rejects_words_format_never_produces() {
+        let digest = [0u8; DIGEST_LENGTH];
+        assert_eq!(Cid::from_format(0, digest), None);
+        // CIDv0 must be base58btc dag-pb sha2-256
+        assert_eq!(Cid::from_format(0x0112_0070, digest), None);
+        assert_eq!(Cid::from_format(0x0012_0055, digest), None);
+        // CIDv1 cannot be base58btc, and needs a known multibase, codec and hash
+        assert_eq!(Cid::from_format(0x1012_0070, digest), None);
+        assert_eq!(Cid::from_format(0x1312_0070, digest), None);
+        assert_eq!(Cid::from_format(0x1112_0072, digest), None);
+        assert_eq!(Cid::from_format(0x1111_0070, digest), None);
+        assert_eq!(Cid::from_format(0x2112_0070, digest), None);
+    }
+}
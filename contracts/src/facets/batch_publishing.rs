@@ -0,0 +1,50 @@
+//! Batch publishing facet: many articles from one creator in one transaction
+
+use crate::*;
+use stylus_sdk::function_selector;
+
+/// Selectors this facet implements, in declaration order
+pub const SELECTORS: &[[u8; 4]] = &[function_selector!(
+    "batchPublishArticles",
+    Vec<String>,
+    Vec<String>,
+    Vec<U256>
+)];
+
+#[cfg(feature = "batch-publishing")]
+#[public]
+impl WikiPayX402 {
+    /// Selectors to register for this facet with `set_facet`
+    pub fn facet_selectors(&self) -> Vec<FixedBytes<4>> {
+        SELECTORS.iter().copied().map(FixedBytes).collect()
+    }
+
+    /// Publish many articles at once (back-catalog imports, up to MAX_PUBLISH_BATCH per call)
+    /// @param ipfs_hashes IPFS CIDs pointing to encrypted content
+    /// @param previews Public preview texts, one per CID
+    /// @param prices USDC amounts (6 decimals), one per CID
+    /// @return (firstId, lastId) of the consecutive ids assigned, in input order
+    pub fn batch_publish_articles(
+        &mut self,
+        ipfs_hashes: Vec<String>,
+        previews: Vec<String>,
+        prices: Vec<U256>,
+    ) -> (U256, U256) {
+        assert!(!ipfs_hashes.is_empty(), "Empty batch");
+        assert!(ipfs_hashes.len() <= MAX_PUBLISH_BATCH, "Too many articles");
+        assert!(
+            ipfs_hashes.len() == previews.len() && ipfs_hashes.len() == prices.len(),
+            "Array length mismatch"
+        );
+
+        let creator = self.vm().msg_sender();
+        let timestamp = U256::from(self.vm().block_timestamp());
+        let first_id = self.article_count.get();
+
+        for ((ipfs_hash, preview), price) in ipfs_hashes.iter().zip(&previews).zip(prices) {
+            self.store_article(creator, ipfs_hash, preview, price, timestamp);
+        }
+
+        (first_id, self.article_count.get() - U256::from(1))
+    }
+}
@@ -0,0 +1,93 @@
+//! Batch unlocks facet: several x402 unlocks in one transaction
+
+use crate::*;
+use stylus_sdk::function_selector;
+
+/// Selectors this facet implements, in declaration order
+pub const SELECTORS: &[[u8; 4]] = &[function_selector!("batchUnlockX402", Vec<UnlockPayload>)];
+
+#[cfg(feature = "batch-unlocks")]
+#[public]
+impl WikiPayX402 {
+    /// Selectors to register for this facet with `set_facet`
+    pub fn facet_selectors(&self) -> Vec<FixedBytes<4>> {
+        SELECTORS.iter().copied().map(FixedBytes).collect()
+    }
+
+    /// Process many x402 unlocks in one transaction (facilitator batching)
+    /// Items are independent: an item with a used nullifier, mismatched nonce, invalid voucher,
+    /// price above its signed value or rejected authorization is skipped instead of reverting
+    /// the whole batch
+    /// @param payloads Up to 256 `unlock_article_x402` argument sets
+    /// @return Bitmap with bit i set when payload i was unlocked
+    pub fn batch_unlock_x402(&mut self, payloads: Vec<UnlockPayload>) -> U256 {
+        assert!(payloads.len() <= MAX_BATCH_UNLOCKS, "Batch too large");
+
+        let contract = self.vm().contract_address();
+        let mut unlocked = U256::ZERO;
+
+        for (i, payload) in payloads.into_iter().enumerate() {
+            if self.token_auth_kinds.get(payload.token) != U8::from(AUTH_EIP3009)
+                || payload.nonce
+                    != self.unlock_nonce(self.nullifier_key(
+                        payload.nullifier,
+                        payload.article_id,
+                        payload.from,
+                    ))
+            {
+                continue;
+            }
+            let checked = self.check_unlock(
+                payload.article_id,
+                payload.token,
+                payload.nullifier,
+                payload.proof,
+                payload.from,
+                &payload.voucher_code,
+            );
+            if checked.is_err() {
+                continue;
+            }
+
+            // Collect payment first so a rejected authorization only skips this item;
+            // Stylus rejects reentrant calls, so the checks above still hold afterwards
+            let amount =
+                self.quote_unlock(payload.article_id, payload.token, &payload.voucher_code);
+            if amount > payload.value {
+                continue;
+            }
+            if amount > U256::ZERO {
+                let result = IERC3009::new(payload.token).receive_with_authorization(
+                    &mut *self,
+                    payload.from,
+                    contract,
+                    payload.value,
+                    payload.valid_after,
+                    payload.valid_before,
+                    payload.nonce,
+                    payload.v,
+                    payload.r,
+                    payload.s,
+                );
+                if result.is_err() {
+                    continue;
+                }
+            }
+
+            self.record_unlock(
+                payload.article_id,
+                payload.token,
+                payload.nullifier,
+                payload.proof,
+                payload.from,
+                &payload.voucher_code,
+            );
+            if amount > U256::ZERO && payload.value > amount {
+                self.transfer_token(payload.token, payload.from, payload.value - amount);
+            }
+            unlocked |= U256::from(1) << i;
+        }
+
+        unlocked
+    }
+}
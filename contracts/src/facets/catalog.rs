@@ -0,0 +1,96 @@
+//! Catalog facet: article and creator listing views
+
+use crate::*;
+#[cfg(feature = "catalog")]
+use paging::page_bounds;
+use stylus_sdk::function_selector;
+
+/// Selectors this facet implements, in declaration order
+pub const SELECTORS: &[[u8; 4]] = &[
+    function_selector!("getArticle", U256),
+    function_selector!("getArticles", U256, U256),
+    function_selector!("getArticlesByCreator", Address, U256, U256),
+    function_selector!("getCreatorArticleCount", Address),
+    function_selector!("getCreatorArticleIds", Address, U256, U256),
+];
+
+#[cfg(feature = "catalog")]
+#[public]
+impl WikiPayX402 {
+    /// Selectors to register for this facet with `set_facet`
+    pub fn facet_selectors(&self) -> Vec<FixedBytes<4>> {
+        SELECTORS.iter().copied().map(FixedBytes).collect()
+    }
+
+    /// Get article data
+    /// Returns: (ipfsHash, preview, price, creator, unlocks, timestamp)
+    /// `price` is the publish price before decay and vouchers; an unlock charges
+    /// get_current_price (or get_voucher_price when a voucher is redeemed)
+    pub fn get_article(&self, article_id: U256) -> (String, String, U256, Address, U256, U256) {
+        let ipfs_hash = self.ipfs_hash(article_id);
+        let preview = self.preview(article_id);
+        let price = self.price_of(article_id);
+        let creator = self.creator_of(article_id);
+        let unlocks = self.unlocks_of(article_id);
+        let timestamp = self.timestamp_of(article_id);
+
+        (ipfs_hash, preview, price, creator, unlocks, timestamp)
+    }
+
+    /// Get a page of the catalog in publication order
+    /// Pages cover ids offset..offset + limit; ids without an article (gaps left by
+    /// import_articles offsets) are skipped, so a page can hold fewer than limit entries
+    /// @param offset First article id of the page
+    /// @param limit Number of ids the page covers (capped at 100)
+    pub fn get_articles(&self, offset: U256, limit: U256) -> Vec<ArticleSummary> {
+        let total = self.article_count.get();
+        let (start, end) = page_bounds(total, offset, limit);
+
+        (start..end)
+            .map(U256::from)
+            .filter(|&id| self.creator_of(id) != Address::ZERO)
+            .map(|id| self.article_summary(id))
+            .collect()
+    }
+
+    /// Get a page of the articles published by a creator, in publication order
+    /// Solidity-era articles are listed once migrated (see migrate_articles), after that point
+    /// @param creator Creator address
+    /// @param offset Index into the creator's articles of the first article to return
+    /// @param limit Maximum number of articles to return (capped at 100)
+    pub fn get_articles_by_creator(
+        &self,
+        creator: Address,
+        offset: U256,
+        limit: U256,
+    ) -> Vec<ArticleSummary> {
+        let ids = self.creator_articles.getter(creator);
+        let (start, end) = page_bounds(U256::from(ids.len()), offset, limit);
+
+        (start..end)
+            .filter_map(|i| ids.get(i))
+            .map(|id| self.article_summary(id))
+            .collect()
+    }
+
+    /// Get the number of articles published by a creator
+    pub fn get_creator_article_count(&self, creator: Address) -> U256 {
+        U256::from(self.creator_articles.getter(creator).len())
+    }
+
+    /// Get a page of the article ids published by a creator, in publication order
+    /// @param creator Creator address
+    /// @param offset Index into the creator's articles of the first id to return
+    /// @param limit Maximum number of ids to return (capped at 100)
+    pub fn get_creator_article_ids(
+        &self,
+        creator: Address,
+        offset: U256,
+        limit: U256,
+    ) -> Vec<U256> {
+        let ids = self.creator_articles.getter(creator);
+        let (start, end) = page_bounds(U256::from(ids.len()), offset, limit);
+
+        (start..end).filter_map(|i| ids.get(i)).collect()
+    }
+}
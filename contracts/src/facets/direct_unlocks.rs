@@ -0,0 +1,123 @@
+//! Direct unlocks facet: EIP-2612 permit and native ETH unlocks
+
+use crate::*;
+use stylus_sdk::function_selector;
+
+/// Selectors this facet implements, in declaration order
+pub const SELECTORS: &[[u8; 4]] = &[
+    function_selector!(
+        "unlockArticlePermit",
+        U256,
+        Address,
+        FixedBytes<32>,
+        FixedBytes<32>,
+        Address,
+        U256,
+        U256,
+        u8,
+        FixedBytes<32>,
+        FixedBytes<32>,
+        String
+    ),
+    function_selector!(
+        "unlockArticleAnonymous",
+        U256,
+        FixedBytes<32>,
+        FixedBytes<32>
+    ),
+];
+
+#[cfg(feature = "direct-unlocks")]
+#[public]
+impl WikiPayX402 {
+    /// Selectors to register for this facet with `set_facet`
+    pub fn facet_selectors(&self) -> Vec<FixedBytes<4>> {
+        SELECTORS.iter().copied().map(FixedBytes).collect()
+    }
+
+    /// Unlock article with an EIP-2612 permit, for tokens without receiveWithAuthorization
+    /// The contract applies the permit and pulls the payment with transferFrom.
+    /// Must be sent by the token holder: the transaction is what binds the article and
+    /// nullifier to them, and no one else can spend allowance granted to this contract
+    /// @param article_id Article to unlock
+    /// @param token EIP-2612 payment token
+    /// @param nullifier Zero-knowledge nullifier (prevents double-spend)
+    /// @param proof Placeholder proof (must be non-zero; not verified on-chain)
+    /// @param owner Token holder who signed the permit (must be the sender, receives refunds)
+    /// @param value Allowance granted by the permit (must cover the price)
+    /// @param deadline Permit deadline timestamp
+    /// @param v Signature component
+    /// @param r Signature component
+    /// @param s Signature component
+    /// @param voucher_code Discount voucher code (empty string for none)
+    #[allow(clippy::too_many_arguments)]
+    pub fn unlock_article_permit(
+        &mut self,
+        article_id: U256,
+        token: Address,
+        nullifier: FixedBytes<32>,
+        proof: FixedBytes<32>,
+        owner: Address,
+        value: U256,
+        deadline: U256,
+        v: u8,
+        r: FixedBytes<32>,
+        s: FixedBytes<32>,
+        voucher_code: String,
+    ) -> bool {
+        assert!(
+            self.token_auth_kinds.get(token) == U8::from(AUTH_EIP2612),
+            "Token does not support EIP-2612"
+        );
+        assert!(
+            owner == self.vm().msg_sender(),
+            "Only owner can unlock with permit"
+        );
+
+        let amount = self.record_unlock(article_id, token, nullifier, proof, owner, &voucher_code);
+
+        if amount > U256::ZERO {
+            assert!(value >= amount, "Permit value too low");
+
+            // A permit copied from the mempool may already have been applied (only the owner
+            // can spend it here), so ignore its failure and let transferFrom check the allowance
+            let contract = self.vm().contract_address();
+            let _ =
+                IERC2612::new(token).permit(&mut *self, owner, contract, value, deadline, v, r, s);
+
+            let result = IERC20::new(token).transfer_from(&mut *self, owner, contract, amount);
+            assert!(matches!(result, Ok(true)), "Token transfer failed");
+        }
+
+        true
+    }
+
+    /// Unlock article anonymously by paying the creator's ETH price
+    /// Overpayment is returned to the sender; shares the nullifier set with x402 unlocks.
+    /// The nullifier is consumed under get_nullifier_key(nullifier, article_id, sender), and
+    /// the sender's own transaction is what binds it to the article
+    /// @param article_id Article to unlock
+    /// @param nullifier Unique nullifier (prevents double-spend)
+    /// @param proof Placeholder proof (must be non-zero; not verified on-chain)
+    #[payable]
+    pub fn unlock_article_anonymous(
+        &mut self,
+        article_id: U256,
+        nullifier: FixedBytes<32>,
+        proof: FixedBytes<32>,
+    ) -> bool {
+        let payer = self.vm().msg_sender();
+        let payment = self.vm().msg_value();
+
+        let amount = self.record_unlock(article_id, ETH, nullifier, proof, payer, "");
+        assert!(payment >= amount, "Insufficient payment");
+
+        // Return overpayment
+        let change = payment - amount;
+        if change > U256::ZERO {
+            self.transfer_token(ETH, payer, change);
+        }
+
+        true
+    }
+}
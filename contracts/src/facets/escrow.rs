@@ -0,0 +1,131 @@
+//! Escrow facet: refund windows, refunds and escrow release
+
+use crate::*;
+use stylus_sdk::function_selector;
+
+/// Selectors this facet implements, in declaration order
+pub const SELECTORS: &[[u8; 4]] = &[
+    function_selector!("setRefundWindow", U256),
+    function_selector!("getRefundWindow", Address),
+    function_selector!("getEscrow", FixedBytes<32>),
+    function_selector!("refund", FixedBytes<32>),
+    function_selector!("releaseEscrow", Vec<FixedBytes<32>>),
+];
+
+#[cfg(feature = "escrow")]
+#[public]
+impl WikiPayX402 {
+    /// Selectors to register for this facet with `set_facet`
+    pub fn facet_selectors(&self) -> Vec<FixedBytes<4>> {
+        SELECTORS.iter().copied().map(FixedBytes).collect()
+    }
+
+    /// Set the refund window applied to future unlocks of the caller's articles
+    /// @param window Seconds after unlock during which the reader can get a refund (0 = no refunds)
+    pub fn set_refund_window(&mut self, window: U256) {
+        assert!(
+            window <= U256::from(MAX_REFUND_WINDOW),
+            "Refund window too long"
+        );
+        let creator = self.vm().msg_sender();
+        self.refund_windows.setter(creator).set(window);
+    }
+
+    /// Get a creator's refund window in seconds
+    pub fn get_refund_window(&self, creator: Address) -> U256 {
+        self.refund_windows.get(creator)
+    }
+
+    /// Get escrow state of an unlock
+    /// Returns: (token, amount, releaseTimestamp); amount is 0 once released or refunded
+    /// @param nullifier_key Key of the unlock (see get_nullifier_key)
+    pub fn get_escrow(&self, nullifier_key: FixedBytes<32>) -> (Address, U256, U256) {
+        (
+            self.unlock_tokens.get(nullifier_key),
+            self.escrow_amounts.get(nullifier_key),
+            self.escrow_releases.get(nullifier_key),
+        )
+    }
+
+    /// Refund an unlock while its refund window is open
+    /// Returns the escrowed payment to the payer and revokes the unlock: the payer's nullifier
+    /// key is free again, so the reader sees the article as locked and can pay for it again.
+    /// Keys are bound to the payer, so no other account can take over the freed key.
+    /// A voucher redeemed by the unlock gets its use back
+    /// @param nullifier_key Key of the unlock to refund (see get_nullifier_key)
+    pub fn refund(&mut self, nullifier_key: FixedBytes<32>) -> U256 {
+        let payer = self.unlock_payers.get(nullifier_key);
+        assert!(
+            payer == self.vm().msg_sender(),
+            "Only payer can request refund"
+        );
+
+        let amount = self.escrow_amounts.get(nullifier_key);
+        assert!(amount > U256::ZERO, "Nothing to refund");
+
+        let now = U256::from(self.vm().block_timestamp());
+        assert!(
+            now < self.escrow_releases.get(nullifier_key),
+            "Refund window closed"
+        );
+
+        let article_id = self.unlock_articles.get(nullifier_key);
+        let token = self.unlock_tokens.get(nullifier_key);
+        let voucher = self.unlock_vouchers.get(nullifier_key);
+
+        // Clear the payer's unlock before transfer (reentrancy protection); the next unlock
+        // under this key needs a fresh EIP-3009 nonce, as the token keeps the old one spent
+        self.nullifiers_used.delete(nullifier_key);
+        self.unlock_amounts.delete(nullifier_key);
+        self.unlock_articles.delete(nullifier_key);
+        self.unlock_payers.delete(nullifier_key);
+        self.unlock_tokens.delete(nullifier_key);
+        self.escrow_amounts.delete(nullifier_key);
+        self.escrow_releases.delete(nullifier_key);
+        self.unlock_vouchers.delete(nullifier_key);
+        let refunds = self.unlock_refunds.get(nullifier_key);
+        self.unlock_refunds
+            .setter(nullifier_key)
+            .set(refunds + U256::from(1));
+
+        let unlocks = self.unlocks_of(article_id);
+        self.set_unlocks(article_id, unlocks - U256::from(1));
+
+        if !voucher.is_zero() {
+            let uses_left = self.voucher_uses_left.get(voucher);
+            self.voucher_uses_left
+                .setter(voucher)
+                .set(uses_left + U256::from(1));
+        }
+
+        self.transfer_token(token, payer, amount);
+
+        amount
+    }
+
+    /// Move escrow whose refund window has closed into creator earnings
+    /// Unknown, refunded, already released or still open unlocks are skipped
+    /// @param nullifier_keys Keys of the unlocks to settle
+    /// @return Number of unlocks released
+    pub fn release_escrow(&mut self, nullifier_keys: Vec<FixedBytes<32>>) -> U256 {
+        let now = U256::from(self.vm().block_timestamp());
+        let mut released = U256::ZERO;
+
+        for nullifier_key in nullifier_keys {
+            let amount = self.escrow_amounts.get(nullifier_key);
+            if amount == U256::ZERO || now < self.escrow_releases.get(nullifier_key) {
+                continue;
+            }
+
+            self.escrow_amounts.setter(nullifier_key).set(U256::ZERO);
+
+            let creator = self.creator_of(self.unlock_articles.get(nullifier_key));
+            let token = self.unlock_tokens.get(nullifier_key);
+            self.credit_earnings(creator, token, amount);
+
+            released += U256::from(1);
+        }
+
+        released
+    }
+}
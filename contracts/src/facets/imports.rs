@@ -0,0 +1,71 @@
+//! Imports facet: one-time imports from previous deployments
+
+use crate::*;
+use stylus_sdk::function_selector;
+
+/// Selectors this facet implements, in declaration order
+pub const SELECTORS: &[[u8; 4]] = &[
+    function_selector!("importArticles", Vec<ImportedArticle>, U256),
+    function_selector!("importNullifiers", Vec<FixedBytes<32>>),
+    function_selector!("lockImports"),
+    function_selector!("importsLocked"),
+];
+
+#[cfg(feature = "imports")]
+#[public]
+impl WikiPayX402 {
+    /// Selectors to register for this facet with `set_facet`
+    pub fn facet_selectors(&self) -> Vec<FixedBytes<4>> {
+        SELECTORS.iter().copied().map(FixedBytes).collect()
+    }
+
+    /// Import articles from a previous deployment (admin only)
+    /// Each article is stored under its original id plus `id_offset`, so several deployments
+    /// whose ids overlap can be merged by giving each its own offset. Target ids must be
+    /// unused; article_count moves past the highest one, and ids jumped over stay empty
+    /// (get_articles skips them, get_article returns zero values). Emits ArticleImported(old, new)
+    /// @param articles Exported article records, in any order
+    /// @param id_offset Added to every original id in this batch (0 keeps the original ids)
+    /// Returns: number of articles imported
+    pub fn import_articles(&mut self, articles: Vec<ImportedArticle>, id_offset: U256) -> U256 {
+        self.only_importer();
+        assert!(articles.len() <= MAX_IMPORT_ARTICLES, "Too many articles");
+
+        for article in &articles {
+            self.import_article(article, id_offset);
+        }
+        U256::from(articles.len())
+    }
+
+    /// Mark nullifiers spent on a previous deployment as used (admin only)
+    /// Old deployments did not bind nullifiers to articles, so these block every article
+    /// @param nullifiers Used nullifiers; ones already recorded are skipped
+    /// Returns: number of nullifiers newly recorded
+    pub fn import_nullifiers(&mut self, nullifiers: Vec<FixedBytes<32>>) -> U256 {
+        self.only_importer();
+        assert!(
+            nullifiers.len() <= MAX_IMPORT_NULLIFIERS,
+            "Too many nullifiers"
+        );
+
+        let mut imported = U256::ZERO;
+        for nullifier in nullifiers {
+            if !self.nullifiers_used.get(nullifier) {
+                self.nullifiers_used.setter(nullifier).set(true);
+                imported += U256::from(1);
+            }
+        }
+        imported
+    }
+
+    /// Permanently disable `import_articles` and `import_nullifiers` (admin only)
+    pub fn lock_imports(&mut self) {
+        self.only_importer();
+        self.imports_locked.set(true);
+    }
+
+    /// Whether importing from previous deployments has been locked
+    pub fn imports_locked(&self) -> bool {
+        self.imports_locked.get()
+    }
+}
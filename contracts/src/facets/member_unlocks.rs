@@ -0,0 +1,103 @@
+//! Member unlocks facet: unlocks paid from the membership pool with a Semaphore proof
+
+use crate::*;
+use stylus_sdk::function_selector;
+
+/// Selectors this facet implements, in declaration order
+pub const SELECTORS: &[[u8; 4]] = &[
+    function_selector!("getMembershipScope", U256),
+    function_selector!("getMemberSignal", Address, U256),
+    function_selector!("unlockArticleMember", U256, U256, U256, U256, [U256; 8]),
+];
+
+#[cfg(feature = "member-unlocks")]
+#[public]
+impl WikiPayX402 {
+    /// Selectors to register for this facet with `set_facet`
+    pub fn facet_selectors(&self) -> Vec<FixedBytes<4>> {
+        SELECTORS.iter().copied().map(FixedBytes).collect()
+    }
+
+    /// Semaphore external nullifier (scope) for one unlock slot of a member on this deployment
+    /// keccak256(abi.encode(slot, chainId, address(this))); each identity gets one nullifier
+    /// per slot, so it can make at most quota member unlocks. Passed to generateProof as is
+    pub fn get_membership_scope(&self, slot: U256) -> U256 {
+        semaphore::scope(slot, self.vm().chain_id(), self.vm().contract_address())
+    }
+
+    /// Semaphore signal of a member unlock sent by `sender`
+    /// keccak256(abi.encode(sender, articleId)), passed to generateProof as is
+    pub fn get_member_signal(&self, sender: Address, article_id: U256) -> U256 {
+        semaphore::signal(sender, article_id)
+    }
+
+    /// Unlock article as an anonymous member of the reader group, spending one unlock slot
+    /// The proof shows the nullifier comes from some registered identity without revealing
+    /// which one. Only articles whose creator opted in (set_member_unlocks) can be unlocked
+    /// this way. The creator is paid the fixed member payout from the membership pool and
+    /// no payer is recorded, so the unlock is not linked to the account that funded the
+    /// identity; any account (e.g. a relayer) can submit it. The signal binds the sender and
+    /// article, so a proof copied from the mempool cannot be reused. Member unlocks cannot
+    /// be refunded.
+    /// Proofs come from `generateProof(identity, group, externalNullifier, signal)` of
+    /// @semaphore-protocol/proof v3, called with externalNullifier = get_membership_scope(slot)
+    /// and signal = get_member_signal(sender, article_id); the v3 verifier hashes both into
+    /// the field the same way generateProof does (see the semaphore module)
+    /// @param article_id Article to unlock
+    /// @param slot Unlock slot of the identity, below the membership quota
+    /// @param merkle_root Identity tree root the proof was generated against
+    /// @param nullifier_hash Semaphore nullifier for get_membership_scope(slot)
+    /// @param proof Groth16 proof points from generateProof
+    pub fn unlock_article_member(
+        &mut self,
+        article_id: U256,
+        slot: U256,
+        merkle_root: U256,
+        nullifier_hash: U256,
+        proof: [U256; 8],
+    ) -> bool {
+        let verifier = self.membership_verifier.get();
+        assert!(verifier != Address::ZERO, "Membership unlocks disabled");
+        assert!(slot < self.member_unlock_quota.get(), "Invalid unlock slot");
+        let creator = self.creator_of(article_id);
+        assert!(creator != Address::ZERO, "Article does not exist");
+        assert!(
+            self.member_unlock_creators.get(creator),
+            "Creator does not accept member unlocks"
+        );
+        assert!(
+            self.known_identity_root(merkle_root),
+            "Unknown identity root"
+        );
+
+        // Raw values: the v3 verifier reduces signal and scope to field elements itself
+        let signal = semaphore::signal(self.vm().msg_sender(), article_id);
+        let scope = self.get_membership_scope(slot);
+
+        let result = ISemaphoreVerifier::new(verifier).verify_proof(
+            &*self,
+            merkle_root,
+            nullifier_hash,
+            signal,
+            scope,
+            proof,
+            U256::from(merkle::DEPTH),
+        );
+        assert!(result.is_ok(), "Invalid membership proof");
+
+        // The slot nullifier is spent for good, whichever article it unlocked
+        let nullifier = FixedBytes::from(nullifier_hash.to_be_bytes::<32>());
+        if let Err(reason) = self.check_nullifier(article_id, nullifier, Address::ZERO) {
+            revert(reason);
+        }
+        self.nullifiers_used.setter(nullifier).set(true);
+
+        // Every identity prepaid quota * payout, so the pool covers all unspent slots
+        let payout = self.member_unlock_payout.get();
+        let pool = self.membership_pool.get();
+        self.membership_pool.set(pool - payout);
+        self.settle_unlock(article_id, ETH, nullifier, Address::ZERO, payout);
+
+        true
+    }
+}
@@ -0,0 +1,140 @@
+//! Membership facet: membership terms and the Semaphore reader group
+
+use crate::*;
+use stylus_sdk::function_selector;
+
+/// Selectors this facet implements, in declaration order
+pub const SELECTORS: &[[u8; 4]] = &[
+    function_selector!("setMembershipVerifier", Address),
+    function_selector!("getMembershipVerifier"),
+    function_selector!("setMembershipTerms", U256, U256),
+    function_selector!("getMembershipTerms"),
+    function_selector!("getMembershipPool"),
+    function_selector!("joinReaders", U256),
+    function_selector!("setMemberUnlocks", bool),
+    function_selector!("acceptsMemberUnlocks", Address),
+    function_selector!("isReader", U256),
+    function_selector!("getIdentityCount"),
+    function_selector!("getIdentityRoot"),
+    function_selector!("isKnownIdentityRoot", U256),
+];
+
+#[cfg(feature = "membership")]
+#[public]
+impl WikiPayX402 {
+    /// Selectors to register for this facet with `set_facet`
+    pub fn facet_selectors(&self) -> Vec<FixedBytes<4>> {
+        SELECTORS.iter().copied().map(FixedBytes).collect()
+    }
+
+    /// Set the Semaphore verifier used for membership unlocks (admin only)
+    /// @param verifier Semaphore v3 SemaphoreVerifier contract (address(0) disables membership unlocks)
+    pub fn set_membership_verifier(&mut self, verifier: Address) {
+        self.only_admin();
+        self.membership_verifier.set(verifier);
+    }
+
+    /// Get the Semaphore verifier used for membership unlocks
+    pub fn get_membership_verifier(&self) -> Address {
+        self.membership_verifier.get()
+    }
+
+    /// Set what a reader group membership buys (admin only, before the first identity joins)
+    /// Terms are fixed once someone has joined, since member unlocks cannot tell which terms
+    /// an identity paid for
+    /// @param quota Member unlocks each identity may make
+    /// @param payout ETH (wei) credited to the creator per member unlock, whatever its price
+    ///        (only creators who opted in with set_member_unlocks accept it)
+    pub fn set_membership_terms(&mut self, quota: U256, payout: U256) {
+        self.only_admin();
+        assert!(
+            self.identity_count.get() == U256::ZERO,
+            "Membership terms locked"
+        );
+        assert!(
+            quota > U256::ZERO && quota <= U256::from(MAX_MEMBER_UNLOCKS),
+            "Invalid quota"
+        );
+        assert!(payout.checked_mul(quota).is_some(), "Invalid payout");
+
+        self.member_unlock_quota.set(quota);
+        self.member_unlock_payout.set(payout);
+    }
+
+    /// Get the membership terms
+    /// Returns: (quota, payout, fee) where fee = quota * payout is charged at join_readers
+    pub fn get_membership_terms(&self) -> (U256, U256, U256) {
+        let quota = self.member_unlock_quota.get();
+        let payout = self.member_unlock_payout.get();
+        (quota, payout, quota * payout)
+    }
+
+    /// Get the ETH left in the membership pool for paying member unlocks
+    pub fn get_membership_pool(&self) -> U256 {
+        self.membership_pool.get()
+    }
+
+    /// Register a reader identity commitment in the anonymous reader group
+    /// The funding account prepays the identity's whole unlock quota here, so member
+    /// unlocks need no payment that could link them back to it. The tree matches a Semaphore
+    /// v3 `Group` with id merkle::GROUP_ID holding the members in join order
+    /// @param identity_commitment Poseidon commitment of the reader's Semaphore identity
+    /// @return Leaf index of the commitment
+    #[payable]
+    pub fn join_readers(&mut self, identity_commitment: U256) -> U256 {
+        assert!(
+            identity_commitment != U256::ZERO && identity_commitment < poseidon::FIELD_MODULUS,
+            "Invalid identity commitment"
+        );
+        assert!(
+            !self.identity_members.get(identity_commitment),
+            "Identity already registered"
+        );
+        let (quota, _, fee) = self.get_membership_terms();
+        assert!(quota > U256::ZERO, "Membership not configured");
+        assert!(self.vm().msg_value() == fee, "Incorrect membership fee");
+
+        let pool = self.membership_pool.get();
+        self.membership_pool.set(pool + self.vm().msg_value());
+
+        self.identity_members.setter(identity_commitment).set(true);
+        self.insert_identity(identity_commitment)
+    }
+
+    /// Accept or stop accepting member unlocks of the caller's articles
+    /// A member unlock pays the fixed payout of get_membership_terms instead of the article's
+    /// price, so articles can only be unlocked this way once their creator opts in
+    /// @param accepted Whether member unlocks are accepted
+    pub fn set_member_unlocks(&mut self, accepted: bool) {
+        let creator = self.vm().msg_sender();
+        self.member_unlock_creators.setter(creator).set(accepted);
+    }
+
+    /// Whether a creator accepts member unlocks of their articles
+    pub fn accepts_member_unlocks(&self, creator: Address) -> bool {
+        self.member_unlock_creators.get(creator)
+    }
+
+    /// Whether an identity commitment is in the reader group
+    pub fn is_reader(&self, identity_commitment: U256) -> bool {
+        self.identity_members.get(identity_commitment)
+    }
+
+    /// Get the number of identities in the reader group
+    pub fn get_identity_count(&self) -> U256 {
+        self.identity_count.get()
+    }
+
+    /// Get the current root of the identity tree
+    pub fn get_identity_root(&self) -> U256 {
+        if self.identity_count.get() == U256::ZERO {
+            return merkle::ZEROS[merkle::DEPTH];
+        }
+        self.identity_roots.get(self.identity_root_index.get())
+    }
+
+    /// Whether a root is one of the last ROOT_HISTORY_SIZE identity tree roots
+    pub fn is_known_identity_root(&self, root: U256) -> bool {
+        self.known_identity_root(root)
+    }
+}
@@ -0,0 +1,61 @@
+//! Metadata facet: article metadata and tag views
+
+use crate::*;
+#[cfg(feature = "metadata")]
+use paging::page_bounds;
+use stylus_sdk::function_selector;
+
+/// Selectors this facet implements, in declaration order
+pub const SELECTORS: &[[u8; 4]] = &[
+    function_selector!("getArticleMetadata", U256),
+    function_selector!("getArticleTags", U256),
+    function_selector!("getTagArticleCount", FixedBytes<32>),
+    function_selector!("getArticlesByTag", FixedBytes<32>, U256, U256),
+];
+
+#[cfg(feature = "metadata")]
+#[public]
+impl WikiPayX402 {
+    /// Selectors to register for this facet with `set_facet`
+    pub fn facet_selectors(&self) -> Vec<FixedBytes<4>> {
+        SELECTORS.iter().copied().map(FixedBytes).collect()
+    }
+
+    /// Get article metadata (empty values for articles published without metadata)
+    /// Returns: (title, language, mimeType, wordCount, contentLength, contentHash)
+    pub fn get_article_metadata(
+        &self,
+        article_id: U256,
+    ) -> (String, String, String, U256, U256, FixedBytes<32>) {
+        (
+            self.titles.getter(article_id).get_string(),
+            self.languages.getter(article_id).get_string(),
+            self.mime_types.getter(article_id).get_string(),
+            self.word_counts.get(article_id),
+            self.content_lengths.get(article_id),
+            self.content_hashes.get(article_id),
+        )
+    }
+
+    /// Get the tag ids of an article
+    pub fn get_article_tags(&self, article_id: U256) -> Vec<FixedBytes<32>> {
+        let tags = self.article_tags.getter(article_id);
+        (0..tags.len()).filter_map(|i| tags.get(i)).collect()
+    }
+
+    /// Get the number of articles carrying a tag
+    pub fn get_tag_article_count(&self, tag: FixedBytes<32>) -> U256 {
+        U256::from(self.tag_articles.getter(tag).len())
+    }
+
+    /// Get a page of the article ids carrying a tag, in publication order
+    /// @param tag Tag id (keccak256 of the normalized tag string)
+    /// @param offset Index into the tag's articles of the first id to return
+    /// @param limit Maximum number of ids to return (capped at 100)
+    pub fn get_articles_by_tag(&self, tag: FixedBytes<32>, offset: U256, limit: U256) -> Vec<U256> {
+        let ids = self.tag_articles.getter(tag);
+        let (start, end) = page_bounds(U256::from(ids.len()), offset, limit);
+
+        (start..end).filter_map(|i| ids.get(i)).collect()
+    }
+}
@@ -0,0 +1,44 @@
+//! Migration facet: moving legacy articles into packed storage
+
+use crate::*;
+use stylus_sdk::function_selector;
+
+/// Selectors this facet implements, in declaration order
+pub const SELECTORS: &[[u8; 4]] = &[
+    function_selector!("migrateArticles", Vec<U256>),
+    function_selector!("isArticlePacked", U256),
+];
+
+#[cfg(feature = "migration")]
+#[public]
+impl WikiPayX402 {
+    /// Selectors to register for this facet with `set_facet`
+    pub fn facet_selectors(&self) -> Vec<FixedBytes<4>> {
+        SELECTORS.iter().copied().map(FixedBytes).collect()
+    }
+
+    /// Move legacy articles into packed storage (anyone can pay for this)
+    /// Articles are also migrated lazily when their price is updated, and are
+    /// added to their creator's article list (get_articles_by_creator) when migrated
+    /// @param article_ids Articles to migrate; unknown, already packed or unpackable ids are skipped
+    /// Returns: number of articles migrated
+    pub fn migrate_articles(&mut self, article_ids: Vec<U256>) -> U256 {
+        assert!(
+            article_ids.len() <= MAX_MIGRATE_ARTICLES,
+            "Too many articles"
+        );
+
+        let mut migrated = U256::ZERO;
+        for article_id in article_ids {
+            if self.migrate_article(article_id) {
+                migrated += U256::from(1);
+            }
+        }
+        migrated
+    }
+
+    /// Whether an article has been moved into packed storage
+    pub fn is_article_packed(&self, article_id: U256) -> bool {
+        self.is_packed(article_id)
+    }
+}
@@ -0,0 +1,132 @@
+//! Facet programs
+//!
+//! The whole contract does not fit in one Stylus program (24 KB compressed), so it is
+//! deployed as a router plus one program per facet, all built from this crate. The router
+//! (default features) keeps initialization, upgrades and admin, and delegates every other
+//! selector to the facet registered for it with `set_facet`. A facet is built by enabling its
+//! feature, e.g. `--features unlocks`, which compiles that facet's public functions instead
+//! of the router's. Every program shares the `WikiPayX402` storage layout, so a facet reads
+//! and writes the proxy's storage exactly as the single program used to.
+//!
+//! Each module lists the selectors it implements in `SELECTORS`, compiled in every build so
+//! the tests below can check that no selector is claimed twice or shadowed by the router.
+
+pub mod batch_publishing;
+pub mod batch_unlocks;
+pub mod catalog;
+pub mod direct_unlocks;
+pub mod escrow;
+pub mod imports;
+pub mod member_unlocks;
+pub mod membership;
+pub mod metadata;
+pub mod migration;
+pub mod payments;
+pub mod pricing;
+pub mod profiles;
+pub mod publishing;
+pub mod signatures;
+pub mod unlocks;
+pub mod vouchers;
+
+/// Selector tables of every facet, by feature name
+pub const FACETS: &[(&str, &[[u8; 4]])] = &[
+    ("batch-publishing", batch_publishing::SELECTORS),
+    ("batch-unlocks", batch_unlocks::SELECTORS),
+    ("catalog", catalog::SELECTORS),
+    ("direct-unlocks", direct_unlocks::SELECTORS),
+    ("escrow", escrow::SELECTORS),
+    ("imports", imports::SELECTORS),
+    ("member-unlocks", member_unlocks::SELECTORS),
+    ("membership", membership::SELECTORS),
+    ("metadata", metadata::SELECTORS),
+    ("migration", migration::SELECTORS),
+    ("payments", payments::SELECTORS),
+    ("pricing", pricing::SELECTORS),
+    ("profiles", profiles::SELECTORS),
+    ("publishing", publishing::SELECTORS),
+    ("signatures", signatures::SELECTORS),
+    ("unlocks", unlocks::SELECTORS),
+    ("vouchers", vouchers::SELECTORS),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WikiPayX402;
+    use alloc::collections::BTreeSet;
+    use stylus_sdk::methods::Purity;
+
+    /// Whether the program being built routes a selector itself
+    /// The public macro only exposes this through its override check: a selector nobody
+    /// implements may be overridden with any purity, an implemented one only with its own
+    fn routes(selector: [u8; 4]) -> bool {
+        let selector = u32::from_be_bytes(selector);
+        !(WikiPayX402::__stylus_allow_override(selector, Purity::Payable)
+            && WikiPayX402::__stylus_allow_override(selector, Purity::Pure))
+    }
+
+    #[test]
+    fn no_selector_is_routed_to_two_facets() {
+        let mut seen = BTreeSet::new();
+        for (name, selectors) in FACETS {
+            for selector in *selectors {
+                assert!(seen.insert(*selector), "{name}: duplicate {selector:02x?}");
+            }
+        }
+    }
+
+    #[cfg(not(feature = "facet"))]
+    #[test]
+    fn router_does_not_shadow_facet_selectors() {
+        for (name, selectors) in FACETS {
+            for selector in *selectors {
+                assert!(
+                    !routes(*selector),
+                    "{name}: router implements {selector:02x?}"
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "facet")]
+    #[test]
+    fn facet_implements_exactly_its_selectors() {
+        let built: Vec<_> = FACETS
+            .iter()
+            .filter(|(name, _)| cfg_feature(name))
+            .collect();
+        assert_eq!(built.len(), 1, "enable exactly one facet feature");
+
+        for (name, selectors) in FACETS {
+            let own = cfg_feature(name);
+            for selector in *selectors {
+                assert_eq!(routes(*selector), own, "{name}: {selector:02x?}");
+            }
+        }
+    }
+
+    #[cfg(feature = "facet")]
+    fn cfg_feature(name: &str) -> bool {
+        match name {
+            "batch-publishing" => cfg!(feature = "batch-publishing"),
+            "batch-unlocks" => cfg!(feature = "batch-unlocks"),
+            "catalog" => cfg!(feature = "catalog"),
+            "direct-unlocks" => cfg!(feature = "direct-unlocks"),
+            "escrow" => cfg!(feature = "escrow"),
+            "imports" => cfg!(feature = "imports"),
+            "member-unlocks" => cfg!(feature = "member-unlocks"),
+            "membership" => cfg!(feature = "membership"),
+            "metadata" => cfg!(feature = "metadata"),
+            "migration" => cfg!(feature = "migration"),
+            "payments" => cfg!(feature = "payments"),
+            "pricing" => cfg!(feature = "pricing"),
+            "profiles" => cfg!(feature = "profiles"),
+            "publishing" => cfg!(feature = "publishing"),
+            "signatures" => cfg!(feature = "signatures"),
+            "unlocks" => cfg!(feature = "unlocks"),
+            "vouchers" => cfg!(feature = "vouchers"),
+            _ => false,
+        }
+    }
+}
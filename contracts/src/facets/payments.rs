@@ -0,0 +1,80 @@
+//! Payments facet: payment tokens and creator earnings
+
+use crate::*;
+use stylus_sdk::function_selector;
+
+/// Selectors this facet implements, in declaration order
+pub const SELECTORS: &[[u8; 4]] = &[
+    function_selector!("addPaymentToken", Address, u8),
+    function_selector!("removePaymentToken", Address),
+    function_selector!("getPaymentToken", Address),
+    function_selector!("getUnlockAmount", FixedBytes<32>),
+    function_selector!("getCreatorEarnings", Address, Address),
+    function_selector!("withdrawEarnings", Address),
+];
+
+#[cfg(feature = "payments")]
+#[public]
+impl WikiPayX402 {
+    /// Selectors to register for this facet with `set_facet`
+    pub fn facet_selectors(&self) -> Vec<FixedBytes<4>> {
+        SELECTORS.iter().copied().map(FixedBytes).collect()
+    }
+
+    /// Accept an ERC-20 token for payments (admin only)
+    /// @param token Token contract address
+    /// @param auth_kind 1 = EIP-3009 receiveWithAuthorization, 2 = EIP-2612 permit
+    pub fn add_payment_token(&mut self, token: Address, auth_kind: u8) {
+        self.only_admin();
+        assert!(token != Address::ZERO, "Invalid token address");
+        assert!(
+            auth_kind == AUTH_EIP3009 || auth_kind == AUTH_EIP2612,
+            "Invalid authorization kind"
+        );
+        self.token_auth_kinds.setter(token).set(U8::from(auth_kind));
+    }
+
+    /// Stop accepting a payment token (admin only)
+    /// Escrow and earnings already held in the token can still be refunded and withdrawn
+    pub fn remove_payment_token(&mut self, token: Address) {
+        self.only_admin();
+        assert!(token != self.usdc_address.get(), "Cannot remove USDC");
+        self.token_auth_kinds.setter(token).set(U8::ZERO);
+    }
+
+    /// Get the authorization kind of a payment token (0 = not accepted)
+    pub fn get_payment_token(&self, token: Address) -> u8 {
+        self.token_auth_kinds.get(token).to::<u8>()
+    }
+
+    /// Get amount charged for an unlock (0 for unknown keys or free unlocks)
+    /// @param nullifier_key Key of the unlock (see get_nullifier_key)
+    pub fn get_unlock_amount(&self, nullifier_key: FixedBytes<32>) -> U256 {
+        self.unlock_amounts.get(nullifier_key)
+    }
+
+    /// Get earnings in a token available for withdrawal
+    /// Settled unlocks are credited to the creator's payout address, so query that address
+    pub fn get_creator_earnings(&self, creator: Address, token: Address) -> U256 {
+        self.creator_earnings.getter(creator).get(token)
+    }
+
+    /// Withdraw the caller's earnings in a token to the caller's payout address
+    pub fn withdraw_earnings(&mut self, token: Address) -> U256 {
+        let account = self.vm().msg_sender();
+        let earnings = self.creator_earnings.getter(account).get(token);
+
+        assert!(earnings != U256::ZERO, "No earnings to withdraw");
+
+        // Reset earnings before transfer (reentrancy protection)
+        self.creator_earnings
+            .setter(account)
+            .setter(token)
+            .set(U256::ZERO);
+
+        let payout = self.payout_of(account);
+        self.transfer_token(token, payout, earnings);
+
+        earnings
+    }
+}
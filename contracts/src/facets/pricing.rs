@@ -0,0 +1,117 @@
+//! Pricing facet: base and token prices and price decay
+
+use crate::*;
+use stylus_sdk::function_selector;
+
+/// Selectors this facet implements, in declaration order
+pub const SELECTORS: &[[u8; 4]] = &[
+    function_selector!("updatePrice", U256, U256),
+    function_selector!("getCurrentPrice", U256),
+    function_selector!("setTokenPrice", U256, Address, U256),
+    function_selector!("getTokenPrice", U256, Address),
+    function_selector!("getPriceDecay", U256),
+    function_selector!("setPriceDecay", U256, U256, U256, U256),
+];
+
+#[cfg(feature = "pricing")]
+#[public]
+impl WikiPayX402 {
+    /// Selectors to register for this facet with `set_facet`
+    pub fn facet_selectors(&self) -> Vec<FixedBytes<4>> {
+        SELECTORS.iter().copied().map(FixedBytes).collect()
+    }
+
+    /// Change an article's USDC price (creator or delegate with price permission)
+    /// @param price USDC amount (6 decimals)
+    pub fn update_price(&mut self, article_id: U256, price: U256) {
+        self.authorize_article(article_id, PERM_SET_PRICE);
+        assert!(
+            price >= self.price_floors.get(article_id),
+            "Price below floor"
+        );
+
+        self.ensure_packed(article_id);
+        if self.is_packed(article_id) {
+            assert!(price <= U256::from(u64::MAX), "Price too large");
+            self.packed_articles
+                .setter(article_id)
+                .price
+                .set(U64::from(price));
+        } else {
+            self.articles.setter(article_id).price.set(price);
+        }
+    }
+
+    /// Get the price currently charged for an article, after applying its decay schedule
+    /// Returns the publish price if the creator has not configured decay
+    pub fn get_current_price(&self, article_id: U256) -> U256 {
+        assert!(
+            self.creator_of(article_id) != Address::ZERO,
+            "Article does not exist"
+        );
+        self.current_price(article_id)
+    }
+
+    /// Set the price of an article in a non-USDC payment token or ETH
+    /// (creator or delegate with price permission)
+    /// Decay and voucher discounts scale this price in proportion to the USDC price; for
+    /// articles with a USDC price of 0 (free in USDC) it is charged as set
+    /// @param article_id Article to price
+    /// @param token Accepted payment token, or address(0) for ETH
+    /// @param price Amount in the token's own decimals, or wei (0 = not payable in this token)
+    pub fn set_token_price(&mut self, article_id: U256, token: Address, price: U256) {
+        self.authorize_article(article_id, PERM_SET_PRICE);
+        assert!(
+            token != self.usdc_address.get(),
+            "USDC price is set at publish"
+        );
+        assert!(
+            token == ETH || self.token_auth_kinds.get(token) != U8::ZERO,
+            "Token not accepted"
+        );
+
+        self.token_prices
+            .setter(article_id)
+            .setter(token)
+            .set(price);
+    }
+
+    /// Get the current price of an article in a payment token, after decay
+    pub fn get_token_price(&self, article_id: U256, token: Address) -> U256 {
+        assert!(
+            self.creator_of(article_id) != Address::ZERO,
+            "Article does not exist"
+        );
+        self.token_amount(article_id, token, self.current_price(article_id))
+    }
+
+    /// Get the price decay schedule of an article
+    /// Returns: (halfLife, floor, freeAfter)
+    pub fn get_price_decay(&self, article_id: U256) -> (U256, U256, U256) {
+        (
+            self.decay_half_lives.get(article_id),
+            self.price_floors.get(article_id),
+            self.free_after.get(article_id),
+        )
+    }
+
+    /// Configure automatic price decay for an article (creator or delegate with price permission)
+    /// @param article_id Article to configure
+    /// @param half_life Seconds after which the price halves, counted from publication (0 = no decay)
+    /// @param floor USDC amount the decayed price never drops below (6 decimals)
+    /// @param free_after Timestamp after which the article is free (0 = never)
+    pub fn set_price_decay(
+        &mut self,
+        article_id: U256,
+        half_life: U256,
+        floor: U256,
+        free_after: U256,
+    ) {
+        self.authorize_article(article_id, PERM_SET_PRICE);
+        assert!(floor <= self.price_of(article_id), "Floor exceeds price");
+
+        self.decay_half_lives.setter(article_id).set(half_life);
+        self.price_floors.setter(article_id).set(floor);
+        self.free_after.setter(article_id).set(free_after);
+    }
+}
@@ -0,0 +1,88 @@
+//! Profiles facet: creator profiles, delegates and payout addresses
+
+use crate::*;
+use stylus_sdk::function_selector;
+
+/// Selectors this facet implements, in declaration order
+pub const SELECTORS: &[[u8; 4]] = &[
+    function_selector!("setDelegate", Address, u8),
+    function_selector!("getDelegatePermissions", Address, Address),
+    function_selector!("setProfile", String, String, String),
+    function_selector!("getProfile", Address),
+    function_selector!("setPayoutAddress", Address),
+    function_selector!("getPayoutAddress", Address),
+];
+
+#[cfg(feature = "profiles")]
+#[public]
+impl WikiPayX402 {
+    /// Selectors to register for this facet with `set_facet`
+    pub fn facet_selectors(&self) -> Vec<FixedBytes<4>> {
+        SELECTORS.iter().copied().map(FixedBytes).collect()
+    }
+
+    /// Grant a delegate scoped permissions over the caller's articles (0 revokes)
+    /// @param delegate Editor address
+    /// @param permissions Bitmask: 1 = publish, 2 = edit previews, 4 = change prices
+    pub fn set_delegate(&mut self, delegate: Address, permissions: u8) {
+        assert!(permissions & !PERM_ALL == 0, "Invalid permissions");
+        let creator = self.vm().msg_sender();
+        self.delegate_permissions
+            .setter(creator)
+            .setter(delegate)
+            .set(U8::from(permissions));
+    }
+
+    /// Get the permissions a creator granted to a delegate
+    pub fn get_delegate_permissions(&self, creator: Address, delegate: Address) -> u8 {
+        self.delegate_permissions
+            .getter(creator)
+            .get(delegate)
+            .to::<u8>()
+    }
+
+    /// Set the caller's creator profile
+    /// The payout address is left as is; change it with set_payout_address
+    /// @param display_name Public name (up to 64 bytes)
+    /// @param avatar_cid IPFS CID of the avatar image (empty for none)
+    /// @param bio_cid IPFS CID of the bio document (empty for none)
+    pub fn set_profile(&mut self, display_name: String, avatar_cid: String, bio_cid: String) {
+        assert!(
+            display_name.len() <= MAX_DISPLAY_NAME_LENGTH,
+            "Display name too long"
+        );
+        for cid in [&avatar_cid, &bio_cid] {
+            if let Err(err) = Cid::parse_optional(cid) {
+                revert(err.message());
+            }
+        }
+
+        let creator = self.vm().msg_sender();
+        self.display_names.setter(creator).set_str(&display_name);
+        self.avatar_cids.setter(creator).set_str(&avatar_cid);
+        self.bio_cids.setter(creator).set_str(&bio_cid);
+    }
+
+    /// Get a creator profile
+    /// Returns: (displayName, avatarCid, bioCid, payoutAddress)
+    pub fn get_profile(&self, creator: Address) -> (String, String, String, Address) {
+        (
+            self.display_names.getter(creator).get_string(),
+            self.avatar_cids.getter(creator).get_string(),
+            self.bio_cids.getter(creator).get_string(),
+            self.payout_addresses.get(creator),
+        )
+    }
+
+    /// Set the address that receives the caller's earnings (e.g. a multisig or cold wallet)
+    /// @param payout_address Payout address (address(0) = pay the publishing address)
+    pub fn set_payout_address(&mut self, payout_address: Address) {
+        let creator = self.vm().msg_sender();
+        self.payout_addresses.setter(creator).set(payout_address);
+    }
+
+    /// Get the address that receives a creator's earnings
+    pub fn get_payout_address(&self, creator: Address) -> Address {
+        self.payout_of(creator)
+    }
+}
@@ -0,0 +1,138 @@
+//! Publishing facet: direct and delegated publishing
+
+use crate::*;
+use stylus_sdk::function_selector;
+
+/// Selectors this facet implements, in declaration order
+pub const SELECTORS: &[[u8; 4]] = &[
+    function_selector!("setMaxPreviewLength", U256),
+    function_selector!("getMaxPreviewLength"),
+    function_selector!("publishArticle", String, String, U256),
+    function_selector!(
+        "publishArticle",
+        String,
+        String,
+        U256,
+        ArticleMetadata,
+        Vec<FixedBytes<32>>
+    ),
+    function_selector!("publishArticleFor", Address, String, String, U256),
+    function_selector!(
+        "publishArticleFor",
+        Address,
+        String,
+        String,
+        U256,
+        ArticleMetadata,
+        Vec<FixedBytes<32>>
+    ),
+    function_selector!("updatePreview", U256, String),
+];
+
+#[cfg(feature = "publishing")]
+#[public]
+impl WikiPayX402 {
+    /// Selectors to register for this facet with `set_facet`
+    pub fn facet_selectors(&self) -> Vec<FixedBytes<4>> {
+        SELECTORS.iter().copied().map(FixedBytes).collect()
+    }
+
+    /// Set the largest inline preview accepted, in bytes (admin only)
+    /// Previews given as "ipfs://<cid>" are not subject to the cap
+    pub fn set_max_preview_length(&mut self, max_length: U256) {
+        self.only_admin();
+        assert!(max_length > U256::ZERO, "Invalid preview length");
+        self.max_preview_length.set(max_length);
+    }
+
+    /// Get the largest inline preview accepted, in bytes
+    pub fn get_max_preview_length(&self) -> U256 {
+        self.preview_length_cap()
+    }
+
+    /// Publish article (stores metadata on-chain, content on IPFS)
+    /// @param ipfs_hash IPFS CID pointing to encrypted content
+    /// @param preview Public preview text (up to the preview cap), or "ipfs://<cid>"
+    /// @param price USDC amount (6 decimals, e.g., 10000 = $0.01)
+    pub fn publish_article(&mut self, ipfs_hash: String, preview: String, price: U256) -> U256 {
+        let creator = self.vm().msg_sender();
+        let timestamp = U256::from(self.vm().block_timestamp());
+
+        self.store_article(creator, &ipfs_hash, &preview, price, timestamp)
+    }
+
+    /// Publish article with structured metadata and topic tags
+    /// Overloads publishArticle so existing callers keep the three-argument selector
+    /// @param ipfs_hash IPFS CID pointing to encrypted content
+    /// @param preview Public preview text
+    /// @param price USDC amount (6 decimals)
+    /// @param metadata Title, language, MIME type, word count, encrypted length and content hash
+    /// @param tags Up to 8 tag ids (keccak256 of the lowercased, trimmed tag string)
+    #[selector(name = "publishArticle")]
+    pub fn publish_article_with_details(
+        &mut self,
+        ipfs_hash: String,
+        preview: String,
+        price: U256,
+        metadata: ArticleMetadata,
+        tags: Vec<FixedBytes<32>>,
+    ) -> U256 {
+        let creator = self.vm().msg_sender();
+        let timestamp = U256::from(self.vm().block_timestamp());
+        let article_id = self.store_article(creator, &ipfs_hash, &preview, price, timestamp);
+
+        self.store_details(article_id, &metadata, &tags);
+
+        article_id
+    }
+
+    /// Publish article on behalf of a creator (requires the publish permission)
+    /// @param creator Creator the article is published under
+    /// @param ipfs_hash IPFS CID pointing to encrypted content
+    /// @param preview Public preview text
+    /// @param price USDC amount (6 decimals)
+    pub fn publish_article_for(
+        &mut self,
+        creator: Address,
+        ipfs_hash: String,
+        preview: String,
+        price: U256,
+    ) -> U256 {
+        self.authorize(creator, PERM_PUBLISH);
+        let timestamp = U256::from(self.vm().block_timestamp());
+
+        self.store_article(creator, &ipfs_hash, &preview, price, timestamp)
+    }
+
+    /// Publish article with metadata and tags on behalf of a creator (publish permission)
+    /// @param creator Creator the article is published under
+    /// @param ipfs_hash IPFS CID pointing to encrypted content
+    /// @param preview Public preview text
+    /// @param price USDC amount (6 decimals)
+    /// @param metadata Title, language, MIME type, word count, encrypted length and content hash
+    /// @param tags Up to 8 tag ids
+    #[selector(name = "publishArticleFor")]
+    pub fn publish_article_for_with_details(
+        &mut self,
+        creator: Address,
+        ipfs_hash: String,
+        preview: String,
+        price: U256,
+        metadata: ArticleMetadata,
+        tags: Vec<FixedBytes<32>>,
+    ) -> U256 {
+        self.authorize(creator, PERM_PUBLISH);
+        let timestamp = U256::from(self.vm().block_timestamp());
+        let article_id = self.store_article(creator, &ipfs_hash, &preview, price, timestamp);
+
+        self.store_details(article_id, &metadata, &tags);
+
+        article_id
+    }
+
+    /// Replace an article's preview text (creator or delegate with edit permission)
+    pub fn update_preview(&mut self, article_id: U256, preview: String) {
+        self.authorize_article(article_id, PERM_EDIT_PREVIEW);
+        self.store_preview(article_id, &preview);
+    }
+}
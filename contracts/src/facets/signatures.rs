@@ -0,0 +1,150 @@
+//! Signatures facet: gasless publishing from EIP-712 signed requests
+
+use crate::*;
+use stylus_sdk::function_selector;
+
+/// Selectors this facet implements, in declaration order
+pub const SELECTORS: &[[u8; 4]] = &[
+    function_selector!(
+        "publishArticleBySig",
+        String,
+        String,
+        U256,
+        U256,
+        U256,
+        u8,
+        FixedBytes<32>,
+        FixedBytes<32>
+    ),
+    function_selector!(
+        "publishArticleBySig",
+        String,
+        String,
+        U256,
+        ArticleMetadata,
+        Vec<FixedBytes<32>>,
+        U256,
+        U256,
+        u8,
+        FixedBytes<32>,
+        FixedBytes<32>
+    ),
+    function_selector!("getPublishNonce", Address),
+    function_selector!("domainSeparator"),
+];
+
+#[cfg(feature = "signatures")]
+#[public]
+impl WikiPayX402 {
+    /// Selectors to register for this facet with `set_facet`
+    pub fn facet_selectors(&self) -> Vec<FixedBytes<4>> {
+        SELECTORS.iter().copied().map(FixedBytes).collect()
+    }
+
+    /// Publish an article on behalf of a creator from an EIP-712 signed request
+    /// Lets the facilitator relay publishing so creators do not need ETH for gas;
+    /// the recovered signer becomes the article creator
+    /// @param ipfs_hash IPFS CID pointing to encrypted content
+    /// @param preview Public preview text
+    /// @param price USDC amount (6 decimals)
+    /// @param nonce Signer's current publish nonce (see get_publish_nonce)
+    /// @param deadline Timestamp after which the signature expires
+    /// @param v Signature component
+    /// @param r Signature component
+    /// @param s Signature component
+    #[allow(clippy::too_many_arguments)]
+    pub fn publish_article_by_sig(
+        &mut self,
+        ipfs_hash: String,
+        preview: String,
+        price: U256,
+        nonce: U256,
+        deadline: U256,
+        v: u8,
+        r: FixedBytes<32>,
+        s: FixedBytes<32>,
+    ) -> U256 {
+        let timestamp = U256::from(self.vm().block_timestamp());
+        assert!(timestamp <= deadline, "Signature expired");
+
+        let request = PublishArticle {
+            ipfsHash: ipfs_hash,
+            preview,
+            price,
+            nonce,
+            deadline,
+        };
+        let digest = eip712::signing_hash(
+            self.domain_separator_hash(),
+            eip712::publish_article_hash(&request),
+        );
+        let creator = self.consume_publish_signature(digest, nonce, v, r, s);
+
+        self.store_article(
+            creator,
+            &request.ipfsHash,
+            &request.preview,
+            price,
+            timestamp,
+        )
+    }
+
+    /// Publish article with metadata and tags from an EIP-712 signed request
+    /// Same as the base publishArticleBySig, signing `PublishArticleWithDetails` instead
+    /// @param metadata Title, language, MIME type, word count, encrypted length and content hash
+    /// @param tags Up to 8 tag ids
+    #[selector(name = "publishArticleBySig")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn publish_article_by_sig_with_details(
+        &mut self,
+        ipfs_hash: String,
+        preview: String,
+        price: U256,
+        metadata: ArticleMetadata,
+        tags: Vec<FixedBytes<32>>,
+        nonce: U256,
+        deadline: U256,
+        v: u8,
+        r: FixedBytes<32>,
+        s: FixedBytes<32>,
+    ) -> U256 {
+        let timestamp = U256::from(self.vm().block_timestamp());
+        assert!(timestamp <= deadline, "Signature expired");
+
+        let request = PublishArticleWithDetails {
+            ipfsHash: ipfs_hash,
+            preview,
+            price,
+            metadata,
+            tags,
+            nonce,
+            deadline,
+        };
+        let digest = eip712::signing_hash(
+            self.domain_separator_hash(),
+            eip712::publish_article_with_details_hash(&request),
+        );
+        let creator = self.consume_publish_signature(digest, nonce, v, r, s);
+
+        let article_id = self.store_article(
+            creator,
+            &request.ipfsHash,
+            &request.preview,
+            price,
+            timestamp,
+        );
+        self.store_details(article_id, &request.metadata, &request.tags);
+
+        article_id
+    }
+
+    /// Get the nonce a creator must sign in their next publish request
+    pub fn get_publish_nonce(&self, creator: Address) -> U256 {
+        self.publish_nonces.get(creator)
+    }
+
+    /// Get the EIP-712 domain separator used for signed publish requests
+    pub fn domain_separator(&self) -> FixedBytes<32> {
+        self.domain_separator_hash()
+    }
+}
@@ -0,0 +1,213 @@
+//! Unlocks facet: x402 article unlocks and nullifier views
+
+use crate::*;
+use stylus_sdk::function_selector;
+
+/// Selectors this facet implements, in declaration order
+pub const SELECTORS: &[[u8; 4]] = &[
+    function_selector!("nullifiersUsed", FixedBytes<32>),
+    function_selector!("nullifiersUsed", FixedBytes<32>, U256, Address),
+    function_selector!("getUnlockNonce", FixedBytes<32>, U256, Address),
+    function_selector!("getNullifierKey", FixedBytes<32>, U256, Address),
+    function_selector!(
+        "unlockArticleX402",
+        U256,
+        Address,
+        FixedBytes<32>,
+        FixedBytes<32>,
+        Address,
+        U256,
+        U256,
+        U256,
+        FixedBytes<32>,
+        u8,
+        FixedBytes<32>,
+        FixedBytes<32>,
+        String
+    ),
+    function_selector!(
+        "unlockArticleX402",
+        U256,
+        FixedBytes<32>,
+        FixedBytes<32>,
+        Address,
+        U256,
+        U256,
+        FixedBytes<32>,
+        u8,
+        FixedBytes<32>,
+        FixedBytes<32>
+    ),
+];
+
+#[cfg(feature = "unlocks")]
+#[public]
+impl WikiPayX402 {
+    /// Selectors to register for this facet with `set_facet`
+    pub fn facet_selectors(&self) -> Vec<FixedBytes<4>> {
+        SELECTORS.iter().copied().map(FixedBytes).collect()
+    }
+
+    /// Check if a nullifier storage slot is taken
+    /// Takes a nullifier key (see get_nullifier_key) or a nullifier recorded before binding;
+    /// kept for callers of the Solidity contract's public mapping getter
+    pub fn nullifiers_used(&self, nullifier: FixedBytes<32>) -> bool {
+        self.nullifiers_used.get(nullifier)
+    }
+
+    /// Check if a reader has used a nullifier to unlock an article
+    /// Nullifiers recorded before binding (imports, Solidity deployment) count for every
+    /// article and reader
+    /// @param payer Account that paid for the unlock
+    #[selector(name = "nullifiersUsed")]
+    pub fn nullifiers_used_for_article(
+        &self,
+        nullifier: FixedBytes<32>,
+        article_id: U256,
+        payer: Address,
+    ) -> bool {
+        self.nullifiers_used.get(nullifier)
+            || self
+                .nullifiers_used
+                .get(self.nullifier_key(nullifier, article_id, payer))
+    }
+
+    /// EIP-3009 nonce an x402 unlock of an article by `payer` must sign
+    /// The nullifier key until the unlock is first refunded, then
+    /// keccak256(abi.encode(nullifierKey, refunds)) so the reader can pay again
+    pub fn get_unlock_nonce(
+        &self,
+        nullifier: FixedBytes<32>,
+        article_id: U256,
+        payer: Address,
+    ) -> FixedBytes<32> {
+        self.unlock_nonce(self.nullifier_key(nullifier, article_id, payer))
+    }
+
+    /// Storage key a nullifier is consumed under when `payer` unlocks an article
+    /// keccak256(abi.encode(nullifier, articleId, payer, chainId, address(this)))
+    pub fn get_nullifier_key(
+        &self,
+        nullifier: FixedBytes<32>,
+        article_id: U256,
+        payer: Address,
+    ) -> FixedBytes<32> {
+        self.nullifier_key(nullifier, article_id, payer)
+    }
+
+    /// Unlock article using x402 protocol
+    /// The facilitator relays the reader's EIP-3009 ReceiveWithAuthorization (payee = this
+    /// contract); the contract pulls the payment into escrow until the refund window closes.
+    /// The reader signs the price quoted before signing as `value`; the unlock charges the
+    /// price at settlement, which decay only lowers, and returns the difference to `from`
+    /// @param article_id Article to unlock
+    /// @param token EIP-3009 payment token (USDC or another accepted token)
+    /// @param nullifier Zero-knowledge nullifier (prevents double-spend)
+    /// @param proof Placeholder proof (must be non-zero; not verified on-chain)
+    /// @param from User's address (EIP-3009 signer, receives refunds)
+    /// @param value EIP-3009 value (the most the reader pays; must cover the current price)
+    /// @param validAfter EIP-3009 validAfter timestamp
+    /// @param validBefore EIP-3009 validBefore timestamp
+    /// @param nonce EIP-3009 nonce; must equal get_unlock_nonce(nullifier, article_id, from)
+    /// @param v Signature component
+    /// @param r Signature component
+    /// @param s Signature component
+    /// @param voucher_code Discount voucher code (empty string for none)
+    #[allow(clippy::too_many_arguments)]
+    pub fn unlock_article_x402(
+        &mut self,
+        article_id: U256,
+        token: Address,
+        nullifier: FixedBytes<32>,
+        proof: FixedBytes<32>,
+        from: Address,
+        value: U256,
+        valid_after: U256,
+        valid_before: U256,
+        nonce: FixedBytes<32>,
+        v: u8,
+        r: FixedBytes<32>,
+        s: FixedBytes<32>,
+        voucher_code: String,
+    ) -> bool {
+        assert!(
+            self.token_auth_kinds.get(token) == U8::from(AUTH_EIP3009),
+            "Token does not support EIP-3009"
+        );
+        // The reader's signature commits to the article through the nonce
+        assert!(
+            nonce == self.unlock_nonce(self.nullifier_key(nullifier, article_id, from)),
+            "Nonce does not match nullifier"
+        );
+
+        let amount = self.record_unlock(article_id, token, nullifier, proof, from, &voucher_code);
+        assert!(value >= amount, "Authorized value too low");
+
+        if amount > U256::ZERO {
+            // Pull the reader's signed authorization into the contract; unlike
+            // transferWithAuthorization, only the payee (this contract) can submit it
+            let contract = self.vm().contract_address();
+            let result = IERC3009::new(token).receive_with_authorization(
+                &mut *self,
+                from,
+                contract,
+                value,
+                valid_after,
+                valid_before,
+                nonce,
+                v,
+                r,
+                s,
+            );
+            assert!(result.is_ok(), "Token authorization failed");
+
+            // Return what the price decayed by since the reader signed
+            if value > amount {
+                self.transfer_token(token, from, value - amount);
+            }
+        }
+
+        true
+    }
+
+    /// Unlock article using x402 protocol, paying in USDC without a voucher
+    /// Shorthand for `unlock_article_x402` with the same signing rules: the reader signs
+    /// ReceiveWithAuthorization to this contract with nonce = get_unlock_nonce(nullifier,
+    /// article_id, from). The signed value must equal the price at settlement, so an
+    /// authorization signed before a decay step reverts; use `unlock_article_x402` to sign
+    /// a maximum instead. Authorizations made for the pre-escrow contract (TransferWithAuthorization
+    /// to the creator with a random nonce) are rejected, so facilitators must sign anew
+    #[selector(name = "unlockArticleX402")]
+    #[allow(clippy::too_many_arguments)]
+    pub fn unlock_article_x402_usdc(
+        &mut self,
+        article_id: U256,
+        nullifier: FixedBytes<32>,
+        proof: FixedBytes<32>,
+        from: Address,
+        valid_after: U256,
+        valid_before: U256,
+        nonce: FixedBytes<32>,
+        v: u8,
+        r: FixedBytes<32>,
+        s: FixedBytes<32>,
+    ) -> bool {
+        let usdc = self.usdc_address.get();
+        let value = self.quote_unlock(article_id, usdc, "");
+        self.unlock_article_x402(
+            article_id,
+            usdc,
+            nullifier,
+            proof,
+            from,
+            value,
+            valid_after,
+            valid_before,
+            nonce,
+            v,
+            r,
+            s,
+            String::new(),
+        )
+    }
+}
@@ -0,0 +1,109 @@
+//! Vouchers facet: creator discount codes
+
+use crate::*;
+use stylus_sdk::function_selector;
+
+/// Selectors this facet implements, in declaration order
+pub const SELECTORS: &[[u8; 4]] = &[
+    function_selector!(
+        "createVoucher",
+        FixedBytes<32>,
+        U256,
+        U256,
+        U256,
+        U256,
+        U256
+    ),
+    function_selector!("revokeVoucher", FixedBytes<32>),
+    function_selector!("getVoucher", Address, FixedBytes<32>),
+    function_selector!("getVoucherPrice", U256, String),
+];
+
+#[cfg(feature = "vouchers")]
+#[public]
+impl WikiPayX402 {
+    /// Selectors to register for this facet with `set_facet`
+    pub fn facet_selectors(&self) -> Vec<FixedBytes<4>> {
+        SELECTORS.iter().copied().map(FixedBytes).collect()
+    }
+
+    /// Register a discount voucher for one or all of the caller's articles
+    /// @param code_hash keccak256 of the voucher code (the code itself stays off-chain)
+    /// @param article_id Article the voucher applies to, or type(uint256).max for all of the caller's articles
+    /// @param percent_off Percentage discount (0-100)
+    /// @param amount_off Fixed USDC discount applied after the percentage (6 decimals)
+    /// @param max_uses Number of unlocks the voucher can be redeemed for
+    /// @param expiry Timestamp after which the voucher is rejected (0 = never)
+    pub fn create_voucher(
+        &mut self,
+        code_hash: FixedBytes<32>,
+        article_id: U256,
+        percent_off: U256,
+        amount_off: U256,
+        max_uses: U256,
+        expiry: U256,
+    ) {
+        let creator = self.vm().msg_sender();
+        if article_id != ALL_ARTICLES {
+            assert!(
+                self.creator_of(article_id) == creator,
+                "Only creator can create vouchers"
+            );
+        }
+        assert!(percent_off <= U256::from(100), "Percent off exceeds 100");
+        assert!(max_uses > U256::ZERO, "Max uses must be greater than 0");
+
+        let key = Self::voucher_key(creator, code_hash);
+        assert!(
+            self.voucher_creators.get(key) == Address::ZERO,
+            "Voucher already exists"
+        );
+
+        self.voucher_creators.setter(key).set(creator);
+        self.voucher_articles.setter(key).set(article_id);
+        self.voucher_percent_off.setter(key).set(percent_off);
+        self.voucher_amount_off.setter(key).set(amount_off);
+        self.voucher_uses_left.setter(key).set(max_uses);
+        self.voucher_expiries.setter(key).set(expiry);
+    }
+
+    /// Revoke a voucher by clearing its remaining uses
+    /// It is also expired, so uses given back by later refunds cannot revive it
+    /// @param code_hash keccak256 of the voucher code
+    pub fn revoke_voucher(&mut self, code_hash: FixedBytes<32>) {
+        let key = Self::voucher_key(self.vm().msg_sender(), code_hash);
+        assert!(
+            self.voucher_creators.get(key) != Address::ZERO,
+            "Voucher does not exist"
+        );
+
+        let now = U256::from(self.vm().block_timestamp());
+        self.voucher_uses_left.setter(key).set(U256::ZERO);
+        self.voucher_expiries.setter(key).set(now);
+    }
+
+    /// Get voucher data
+    /// Returns: (articleId, percentOff, amountOff, usesLeft, expiry)
+    pub fn get_voucher(
+        &self,
+        creator: Address,
+        code_hash: FixedBytes<32>,
+    ) -> (U256, U256, U256, U256, U256) {
+        let key = Self::voucher_key(creator, code_hash);
+
+        (
+            self.voucher_articles.get(key),
+            self.voucher_percent_off.get(key),
+            self.voucher_amount_off.get(key),
+            self.voucher_uses_left.get(key),
+            self.voucher_expiries.get(key),
+        )
+    }
+
+    /// Get the price a reader pays for an article when redeeming a voucher code
+    /// Reverts if the code is not valid for the article
+    pub fn get_voucher_price(&self, article_id: U256, code: String) -> U256 {
+        let key = self.valid_voucher_key(article_id, &code);
+        self.discounted_price(article_id, key)
+    }
+}
@@ -1,71 +1,689 @@
-#![cfg_attr(not(any(test, feature = "export-abi")), no_main)]
-#![cfg_attr(not(any(test, feature = "export-abi")), no_std)]
+#![cfg_attr(all(target_arch = "wasm32", not(feature = "export-abi")), no_main)]
+#![cfg_attr(all(target_arch = "wasm32", not(feature = "export-abi")), no_std)]
+#![recursion_limit = "256"]
+// Each program (the router or one facet) compiles only the helpers its functions use
+#![allow(dead_code)]
 
 #[macro_use]
 extern crate alloc;
 
+pub mod cid;
+pub mod eip712;
+pub mod facets;
+pub mod merkle;
+pub mod nullifier;
+pub mod paging;
+pub mod poseidon;
+pub mod pricing;
+pub mod semaphore;
+
 use alloc::string::String;
+use alloc::vec::Vec;
+use alloy_sol_types::{sol, SolEvent};
+use cid::Cid;
+#[cfg(not(feature = "facet"))]
+use stylus_sdk::{abi::Bytes, ArbResult};
 use stylus_sdk::{
-    alloy_primitives::{Address, FixedBytes, U256},
-    block, msg,
+    alloy_primitives::{b256, Address, FixedBytes, U256, U32, U64, U8},
+    crypto,
     prelude::*,
 };
 
+/// Abort on panic; Stylus reverts a trapped call without return data either way
+#[cfg(all(target_arch = "wasm32", not(feature = "export-abi")))]
+#[panic_handler]
+fn panic(_info: &core::panic::PanicInfo) -> ! {
+    core::arch::wasm32::unreachable()
+}
+
+/// Revert with a reason only known at runtime
+/// `panic!("{}", reason)` would link the formatting machinery into every program for a
+/// message the panic handler above discards
+#[cfg(all(target_arch = "wasm32", not(feature = "export-abi")))]
+fn revert(_reason: &str) -> ! {
+    core::arch::wasm32::unreachable()
+}
+
+/// Revert with a reason only known at runtime
+#[cfg(not(all(target_arch = "wasm32", not(feature = "export-abi"))))]
+fn revert(reason: &str) -> ! {
+    panic!("{}", reason)
+}
+
+/// Voucher scope marker: the voucher applies to every article of its creator
+const ALL_ARTICLES: U256 = U256::MAX;
+
+/// Longest refund window a creator can configure (30 days)
+const MAX_REFUND_WINDOW: u64 = 30 * 24 * 60 * 60;
+
+/// Payment token authorization schemes (0 = token not accepted)
+const AUTH_EIP3009: u8 = 1;
+const AUTH_EIP2612: u8 = 2;
+
+/// Token marker for native ETH prices, escrow and earnings
+const ETH: Address = Address::ZERO;
+
+/// Largest batch accepted by `batch_unlock_x402` (one bit per item in the result)
+const MAX_BATCH_UNLOCKS: usize = 256;
+
+/// Most articles `import_articles` accepts per call
+const MAX_IMPORT_ARTICLES: usize = 100;
+
+/// Most nullifiers `import_nullifiers` accepts per call
+const MAX_IMPORT_NULLIFIERS: usize = 256;
+
+/// Most articles `migrate_articles` moves per call
+const MAX_MIGRATE_ARTICLES: usize = 100;
+
+/// Most articles `batch_publish_articles` publishes per call
+const MAX_PUBLISH_BATCH: usize = 100;
+
+/// Longest creator display name, in bytes
+const MAX_DISPLAY_NAME_LENGTH: usize = 64;
+
+/// Longest article title, in bytes
+const MAX_TITLE_LENGTH: usize = 256;
+
+/// Longest language code (BCP 47 tag, e.g. "en" or "pt-BR"), in bytes
+const MAX_LANGUAGE_LENGTH: usize = 16;
+
+/// Longest MIME type ("type/subtype", RFC 6838 caps each part at 127 bytes), in bytes
+const MAX_MIME_TYPE_LENGTH: usize = 255;
+
+/// Preview byte cap used until the admin configures one
+const DEFAULT_MAX_PREVIEW_LENGTH: u64 = 1024;
+
+/// Prefix marking a preview that lives on IPFS instead of in contract storage
+const IPFS_PREVIEW_PREFIX: &str = "ipfs://";
+
+/// Most tags an article can carry
+const MAX_TAGS: usize = 8;
+
+/// Delegate permission bits
+const PERM_PUBLISH: u8 = 1;
+const PERM_EDIT_PREVIEW: u8 = 2;
+const PERM_SET_PRICE: u8 = 4;
+const PERM_ALL: u8 = PERM_PUBLISH | PERM_EDIT_PREVIEW | PERM_SET_PRICE;
+
+/// ecrecover precompile
+const ECRECOVER: Address = Address::with_last_byte(1);
+
+/// Largest unlock quota a reader group membership can grant
+const MAX_MEMBER_UNLOCKS: u64 = 1000;
+
+/// Storage layout version written by `initialize`; bump when an upgrade changes the layout
+const STORAGE_VERSION: u64 = 1;
+
+/// ERC-1967 implementation slot: keccak256("eip1967.proxy.implementation") - 1
+const IMPLEMENTATION_SLOT: FixedBytes<32> =
+    b256!("360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc");
+
+/// ERC-1967 admin slot: keccak256("eip1967.proxy.admin") - 1
+const ADMIN_SLOT: FixedBytes<32> =
+    b256!("b53127684a568b3173ae13b9f8a6016e243e63b6e8ee1178d6a717850b5d6103");
+
+sol! {
+    /// Arguments of one `unlock_article_x402` call, for batched submission
+    #[derive(AbiType)]
+    struct UnlockPayload {
+        uint256 article_id;
+        address token;
+        bytes32 nullifier;
+        bytes32 proof;
+        address from;
+        uint256 value;
+        uint256 valid_after;
+        uint256 valid_before;
+        bytes32 nonce;
+        uint8 v;
+        bytes32 r;
+        bytes32 s;
+        string voucher_code;
+    }
+
+    /// Structured article metadata supplied at publish time
+    /// Also signed inside PublishArticleWithDetails, so fields are camelCase like the other
+    /// EIP-712 types
+    #[derive(AbiType)]
+    struct ArticleMetadata {
+        string title;
+        string language;
+        string mimeType;
+        uint256 wordCount;
+        uint256 contentLength;
+        bytes32 contentHash;
+    }
+
+    /// Article record exported from a previous deployment
+    #[derive(AbiType)]
+    struct ImportedArticle {
+        uint256 article_id;
+        string ipfs_hash;
+        string preview;
+        uint256 price;
+        address creator;
+        uint256 unlocks;
+        uint256 timestamp;
+    }
+
+    /// ERC-1967 upgrade event
+    event Upgraded(address indexed implementation);
+
+    /// An article from a previous deployment was stored under a new id
+    event ArticleImported(uint256 indexed sourceId, uint256 indexed articleId);
+
+    /// EIP-712 publish request signed by a creator for gasless publishing
+    struct PublishArticle {
+        string ipfsHash;
+        string preview;
+        uint256 price;
+        uint256 nonce;
+        uint256 deadline;
+    }
+
+    /// EIP-712 publish request that also carries metadata and tags
+    struct PublishArticleWithDetails {
+        string ipfsHash;
+        string preview;
+        uint256 price;
+        ArticleMetadata metadata;
+        bytes32[] tags;
+        uint256 nonce;
+        uint256 deadline;
+    }
+}
+
+/// Catalog entry returned by the paginated view functions
+/// (id, ipfsHash, preview, price, creator, unlocks, timestamp)
+type ArticleSummary = (U256, String, String, U256, Address, U256, U256);
+
+sol_interface! {
+    interface IERC20 {
+        function transfer(address to, uint256 value) external returns (bool);
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+    }
+
+    interface IERC2612 {
+        #[allow(clippy::too_many_arguments)]
+        function permit(
+            address owner,
+            address spender,
+            uint256 value,
+            uint256 deadline,
+            uint8 v,
+            bytes32 r,
+            bytes32 s
+        ) external;
+    }
+
+    interface IERC1822Proxiable {
+        function proxiableUUID() external view returns (bytes32);
+    }
+
+    interface ISemaphoreVerifier {
+        #[allow(clippy::too_many_arguments)]
+        function verifyProof(
+            uint256 merkle_tree_root,
+            uint256 nullifier_hash,
+            uint256 signal,
+            uint256 external_nullifier,
+            uint256[8] proof,
+            uint256 merkle_tree_depth
+        ) external view;
+    }
+
+    interface IERC3009 {
+        #[allow(clippy::too_many_arguments)]
+        function receiveWithAuthorization(
+            address from,
+            address to,
+            uint256 value,
+            uint256 valid_after,
+            uint256 valid_before,
+            bytes32 nonce,
+            uint8 v,
+            bytes32 r,
+            bytes32 s
+        ) external;
+    }
+}
+
 sol_storage! {
     #[entrypoint]
     pub struct WikiPayX402 {
-        // Article storage
-        mapping(uint256 => string) ipfs_hashes;
-        mapping(uint256 => string) previews;
-        mapping(uint256 => uint256) prices;          // USDC amount (6 decimals)
-        mapping(uint256 => address) creators;
-        mapping(uint256 => uint256) unlocks;
-        mapping(uint256 => uint256) timestamps;
-
-        // Nullifier tracking (prevents double-spend)
+        // Slots 0-3 mirror contracts-solidity/contracts/WikiPayX402.sol so a proxy can be
+        // repointed from the Solidity implementation without losing state. Do not reorder.
+
+        // Slot 0: Solidity article records, read until an article is moved into
+        // `packed_articles` (previews, unparseable CID strings and articles priced
+        // above u64 stay here for good)
+        mapping(uint256 => Article) articles;
+
+        // Slot 1: nullifier tracking (prevents double-spend)
         mapping(bytes32 => bool) nullifiers_used;
 
-        // Total articles
+        // Slot 2: total articles
         uint256 article_count;
 
-        // USDC contract address (Circle USDC on Arbitrum One)
+        // Slot 3: USDC contract address (Circle USDC on Arbitrum One)
         address usdc_address;
+
+        // Price decay schedule (archive pricing)
+        mapping(uint256 => uint256) decay_half_lives;  // seconds per halving, 0 = no decay
+        mapping(uint256 => uint256) price_floors;      // USDC amount the price never decays below
+        mapping(uint256 => uint256) free_after;        // timestamp after which article is free, 0 = never
+
+        // Discount vouchers, keyed by keccak256(creator, keccak256(code))
+        mapping(bytes32 => address) voucher_creators;
+        mapping(bytes32 => uint256) voucher_articles;   // article id, or ALL_ARTICLES
+        mapping(bytes32 => uint256) voucher_percent_off;
+        mapping(bytes32 => uint256) voucher_amount_off; // USDC amount (6 decimals)
+        mapping(bytes32 => uint256) voucher_uses_left;
+        mapping(bytes32 => uint256) voucher_expiries;   // timestamp, 0 = never
+
+        // Amount charged per unlock, in the unlock token (after decay and vouchers)
+        mapping(bytes32 => uint256) unlock_amounts;
+
+        // Refund escrow: payments are held until the creator's refund window closes
+        mapping(address => uint256) refund_windows;     // seconds, per creator
+        mapping(bytes32 => uint256) unlock_articles;    // nullifier => article id
+        mapping(bytes32 => address) unlock_payers;      // nullifier => payer (receives refunds)
+        mapping(bytes32 => uint256) escrow_amounts;     // token amount still held for the unlock
+        mapping(bytes32 => uint256) escrow_releases;    // timestamp the refund window closes
+
+        // Mapping: payout address => token => withdrawable earnings
+        mapping(address => mapping(address => uint256)) creator_earnings;
+
+        // Contract administrator (manages the payment token registry)
+        address admin;
+
+        // Accepted ERC-20 payment tokens: token => AUTH_EIP3009 / AUTH_EIP2612
+        mapping(address => uint8) token_auth_kinds;
+
+        // Per-token article prices (USDC price lives in `articles`, ETH under address(0))
+        mapping(uint256 => mapping(address => uint256)) token_prices;
+
+        // Token each unlock was paid in (address(0) for ETH)
+        mapping(bytes32 => address) unlock_tokens;
+
+        // Mapping: creator => article ids in publication order
+        mapping(address => uint256[]) creator_articles;
+
+        // Creator profiles
+        mapping(address => string) display_names;
+        mapping(address => string) avatar_cids;       // IPFS CID of the avatar image
+        mapping(address => string) bio_cids;          // IPFS CID of the bio document
+        mapping(address => address) payout_addresses;
+
+        // Mapping: creator => next EIP-712 publish request nonce
+        mapping(address => uint256) publish_nonces;
+
+        // Mapping: creator => delegate => PERM_* bitmask (editorial teams)
+        mapping(address => mapping(address => uint8)) delegate_permissions;
+
+        // Article metadata
+        mapping(uint256 => string) titles;
+        mapping(uint256 => string) languages;         // BCP 47 language code
+        mapping(uint256 => string) mime_types;        // MIME type of the decrypted content
+        mapping(uint256 => uint256) word_counts;
+        mapping(uint256 => uint256) content_lengths;  // encrypted content size in bytes
+        mapping(uint256 => bytes32) content_hashes;   // hash of the encrypted content
+
+        // Topic tags, identified by keccak256 of the normalized tag string
+        mapping(bytes32 => uint256[]) tag_articles;   // tag => article ids in publication order
+        mapping(uint256 => bytes32[]) article_tags;
+
+        // Preview size cap in bytes (0 = DEFAULT_MAX_PREVIEW_LENGTH)
+        uint256 max_preview_length;
+
+        // Previews stored on IPFS ("ipfs://<cid>"), kept as binary CIDs
+        mapping(uint256 => bytes32) preview_cid_digests;
+        mapping(uint256 => uint32) preview_cid_formats; // 0 = inline text in articles
+
+        // Packed article storage (3 slots instead of the 6 of `Article`)
+        mapping(uint256 => PackedArticle) packed_articles;
+
+        // Layout version set by `initialize` (0 = uninitialized proxy storage)
+        uint64 storage_version;
+
+        // Set once importing from previous deployments is finished; never cleared
+        bool imports_locked;
+
+        // Unused since commit-reveal unlocks were removed; kept so later fields keep their slots
+        mapping(bytes32 => uint256) unlock_commit_blocks;
+        mapping(bytes32 => address) unlock_committers;
+
+        // Semaphore-style reader group: incremental Merkle tree of identity commitments
+        address membership_verifier;                    // Semaphore v3 SemaphoreVerifier
+        uint256 identity_count;
+        mapping(uint256 => uint256) identity_side_nodes; // level => last left node
+        mapping(uint256 => uint256) identity_roots;      // ring buffer of recent roots
+        uint256 identity_root_index;
+        mapping(uint256 => bool) identity_members;       // commitment => registered
+
+        // Paid reader group: each identity prepays quota * payout ETH into the pool and
+        // may then unlock up to quota articles, each paying the creator a fixed payout
+        uint256 member_unlock_quota;
+        uint256 member_unlock_payout;
+        uint256 membership_pool;
+
+        // Times each nullifier key was refunded; changes the EIP-3009 nonce of the next unlock
+        mapping(bytes32 => uint256) unlock_refunds;
+
+        // Creators who accept member unlocks of their articles at the fixed member payout
+        mapping(address => bool) member_unlock_creators;
+
+        // Voucher redeemed by each unlock (nullifier key => voucher key, zero for none),
+        // so a refund can return the use
+        mapping(bytes32 => bytes32) unlock_vouchers;
+
+        // Facet program each selector the router does not implement is delegated to
+        mapping(bytes4 => address) facets;
+    }
+
+    /// `struct Article` of the Solidity contract, field for field
+    pub struct Article {
+        string ipfs_hash;      // CID string (binary in packed_articles once migrated)
+        string preview;        // Inline preview text
+        uint256 price;         // USDC amount (6 decimals)
+        address creator;
+        uint256 unlocks;
+        uint256 timestamp;
+    }
+
+    /// Article fields packed as tightly as Solidity would lay them out:
+    /// slot 0 = creator + price + cid_format, slot 1 = timestamp + unlocks, slot 2 = cid_digest
+    pub struct PackedArticle {
+        address creator;
+        uint64 price;          // USDC amount (6 decimals)
+        uint32 cid_format;     // 0 = CID kept as a string in articles
+        uint64 timestamp;
+        uint64 unlocks;
+        bytes32 cid_digest;
     }
 }
 
+#[cfg(not(feature = "facet"))]
 #[public]
 impl WikiPayX402 {
+    /// Initialize a direct deployment with USDC accepted via EIP-3009
+    /// The deploying account becomes admin. Behind a proxy this only initializes the
+    /// implementation's own storage, which also stops anyone from initializing (and then
+    /// upgrading) the bare implementation
+    /// @param usdc_address Circle USDC contract address on Arbitrum One
+    #[constructor]
+    pub fn constructor(&mut self, usdc_address: Address) {
+        // Constructors run through the Stylus deployer, so use the originating account
+        let admin = self.vm().tx_origin();
+        self.init(usdc_address, admin);
+    }
+
+    /// Initialize proxy storage
+    /// Only callable from the proxy constructor or by the ERC-1967 proxy admin (e.g. through
+    /// a transparent proxy's upgradeToAndCall), so nobody can front-run the initialization
+    /// Articles and nullifiers already in storage (e.g. from the Solidity version) are kept
+    /// @param usdc_address USDC contract address
+    /// @param admin Contract administrator
+    pub fn initialize(&mut self, usdc_address: Address, admin: Address) {
+        assert!(
+            self.implementation() != Address::ZERO,
+            "Must be called through proxy"
+        );
+        let in_proxy_constructor = self.vm().code_size(self.vm().contract_address()) == 0;
+        assert!(
+            in_proxy_constructor || self.proxy_admin() == self.vm().msg_sender(),
+            "Only proxy deployer or admin"
+        );
+        assert!(
+            self.storage_version.get() == U64::ZERO,
+            "Already initialized"
+        );
+        assert!(admin != Address::ZERO, "Invalid admin");
+        self.init(usdc_address, admin);
+    }
+
+    /// Bring storage written by an older implementation up to the current layout (admin only)
+    /// Call it through upgrade_to_and_call so no other call sees the old layout
+    pub fn reinitialize(&mut self) {
+        self.only_admin();
+        let version = self.storage_version.get().to::<u64>();
+        assert!(version != 0, "Not initialized");
+        assert!(version < STORAGE_VERSION, "Already initialized");
+
+        // Per-version layout migrations run here, oldest first
+        self.storage_version.set(U64::from(STORAGE_VERSION));
+    }
+
+    /// Get the storage layout version (0 = not initialized)
+    pub fn get_storage_version(&self) -> u64 {
+        self.storage_version.get().to::<u64>()
+    }
+
+    /// Get the current implementation (address(0) when not called through a proxy)
+    pub fn get_implementation(&self) -> Address {
+        self.implementation()
+    }
+
+    /// ERC-1822: storage slot this implementation upgrades through
+    /// Reverts when called through a proxy, so a proxy can never be set as an implementation
+    #[selector(name = "proxiableUUID")]
+    pub fn proxiable_uuid(&self) -> FixedBytes<32> {
+        assert!(
+            self.implementation() == Address::ZERO,
+            "Must not be called through proxy"
+        );
+        IMPLEMENTATION_SLOT
+    }
+
+    /// Point the proxy at a new implementation (admin only, UUPS)
+    /// @param new_implementation Deployed implementation exposing a matching proxiableUUID
+    pub fn upgrade_to(&mut self, new_implementation: Address) {
+        self.only_admin();
+        self.set_implementation(new_implementation);
+    }
+
+    /// Point the proxy at a new implementation and call it in the same transaction (admin only)
+    /// Use this to run `reinitialize` so the upgrade and the storage migration are atomic
+    /// @param new_implementation Deployed implementation exposing a matching proxiableUUID
+    /// @param data Calldata delegatecalled on the new implementation (empty to skip the call)
+    pub fn upgrade_to_and_call(&mut self, new_implementation: Address, data: Bytes) {
+        self.only_admin();
+        self.set_implementation(new_implementation);
+
+        if !data.is_empty() {
+            let context: &mut Self = self;
+            // SAFETY: the admin vetted the implementation, which runs in this proxy's
+            // context exactly as it will for every later call
+            let result = unsafe {
+                context
+                    .vm()
+                    .delegate_call(&context, new_implementation, &data)
+            };
+            assert!(result.is_ok(), "Upgrade call failed");
+        }
+    }
+
+    /// Get contract admin
+    pub fn get_admin(&self) -> Address {
+        self.admin.get()
+    }
+
+    /// Hand over admin rights (admin only)
+    pub fn transfer_admin(&mut self, new_admin: Address) {
+        self.only_admin();
+        assert!(new_admin != Address::ZERO, "Invalid admin address");
+        self.admin.set(new_admin);
+    }
+
     /// Get USDC address
     pub fn get_usdc_address(&self) -> Address {
         self.usdc_address.get()
     }
 
     /// Get total articles count
+    /// One past the highest article id; ids skipped by import_articles offsets hold no article
     pub fn get_total_articles(&self) -> U256 {
         self.article_count.get()
     }
 
-    /// Publish article (stores metadata on-chain, content on IPFS)
-    /// @param ipfs_hash IPFS CID pointing to encrypted content
-    /// @param preview Public preview text
-    /// @param price USDC amount (6 decimals, e.g., 10000 = $0.01)
-    pub fn publish_article(
+    /// Route selectors to a facet program (admin only)
+    /// Selectors the router implements itself never reach a facet
+    /// @param selectors Selectors to route, usually the facet's own facetSelectors()
+    /// @param facet Facet program (address(0) unroutes the selectors)
+    pub fn set_facet(&mut self, selectors: Vec<FixedBytes<4>>, facet: Address) {
+        self.only_admin();
+        for selector in selectors {
+            self.facets.setter(selector).set(facet);
+        }
+    }
+
+    /// Get the facet program a selector is routed to (address(0) if none)
+    pub fn get_facet(&self, selector: FixedBytes<4>) -> Address {
+        self.facets.get(selector)
+    }
+
+    /// Delegate calls the router does not implement to the facet registered for the selector
+    /// The facet runs against this contract's storage with the original sender and value
+    #[fallback]
+    #[payable]
+    pub fn fallback(&mut self, calldata: &[u8]) -> ArbResult {
+        assert!(calldata.len() >= 4, "Unknown function");
+        let facet = self.facets.get(FixedBytes::<4>::from_slice(&calldata[..4]));
+        assert!(facet != Address::ZERO, "Unknown function");
+
+        let context: &mut Self = self;
+        // SAFETY: only the admin registers facets, which are built from this crate and share
+        // its storage layout
+        let result = unsafe { context.vm().delegate_call(&context, facet, calldata) };
+        result.map_err(Into::into)
+    }
+}
+
+// Internal functions (not exposed via ABI)
+impl WikiPayX402 {
+    /// Shared initialization for direct deployments and proxies
+    fn init(&mut self, usdc_address: Address, admin: Address) {
+        assert!(usdc_address != Address::ZERO, "Invalid USDC address");
+        self.usdc_address.set(usdc_address);
+        self.token_auth_kinds
+            .setter(usdc_address)
+            .set(U8::from(AUTH_EIP3009));
+        self.admin.set(admin);
+        self.storage_version.set(U64::from(STORAGE_VERSION));
+    }
+
+    /// Whether a root is one of the last ROOT_HISTORY_SIZE identity tree roots
+    fn known_identity_root(&self, root: U256) -> bool {
+        if root == U256::ZERO {
+            return false;
+        }
+        (0..merkle::ROOT_HISTORY_SIZE).any(|i| self.identity_roots.get(U256::from(i)) == root)
+    }
+
+    /// Append a leaf to the identity tree and record the new root
+    fn insert_identity(&mut self, leaf: U256) -> U256 {
+        let index = self.identity_count.get();
+        assert!(
+            index < U256::from(1u64 << merkle::DEPTH),
+            "Identity tree full"
+        );
+
+        let root = merkle::insert(&mut self.identity_side_nodes, index, leaf);
+
+        // Keep the last ROOT_HISTORY_SIZE roots; the first root goes to slot 0
+        let root_index = if index == U256::ZERO {
+            U256::ZERO
+        } else {
+            (self.identity_root_index.get() + U256::from(1)) % U256::from(merkle::ROOT_HISTORY_SIZE)
+        };
+        self.identity_root_index.set(root_index);
+        self.identity_roots.setter(root_index).set(root);
+        self.identity_count.set(index + U256::from(1));
+
+        index
+    }
+
+    /// Admin check for the one-time import functions
+    fn only_importer(&self) {
+        self.only_admin();
+        assert!(!self.imports_locked.get(), "Imports locked");
+    }
+
+    /// Validate a UUPS upgrade target and write it to the ERC-1967 slot
+    fn set_implementation(&mut self, new_implementation: Address) {
+        assert!(
+            self.implementation() != Address::ZERO,
+            "Must be called through proxy"
+        );
+        assert!(
+            self.vm().code_size(new_implementation) > 0,
+            "Implementation has no code"
+        );
+
+        // Refuse targets that cannot upgrade again, which would brick the proxy
+        let uuid = IERC1822Proxiable::new(new_implementation).proxiable_uuid(&*self);
+        assert!(uuid == Ok(IMPLEMENTATION_SLOT), "Implementation not UUPS");
+
+        // SAFETY: the ERC-1967 slot is reserved for the implementation address and
+        // does not overlap with any field of the sol_storage layout
+        unsafe {
+            self.vm().storage_cache_bytes32(
+                U256::from_be_bytes(IMPLEMENTATION_SLOT.0),
+                new_implementation.into_word(),
+            );
+        }
+        self.vm().flush_cache(false);
+
+        self.emit_indexed::<Upgraded>(&[new_implementation.into_word()]);
+    }
+
+    /// Proxy admin from the ERC-1967 admin slot (zero for UUPS proxies)
+    fn proxy_admin(&self) -> Address {
+        let word = self
+            .vm()
+            .storage_load_bytes32(U256::from_be_bytes(ADMIN_SLOT.0));
+        Address::from_word(word)
+    }
+
+    /// Implementation address from the ERC-1967 slot (zero outside a proxy)
+    fn implementation(&self) -> Address {
+        let word = self
+            .vm()
+            .storage_load_bytes32(U256::from_be_bytes(IMPLEMENTATION_SLOT.0));
+        Address::from_word(word)
+    }
+
+    /// Store a new article under the next id and return that id
+    fn store_article(
         &mut self,
-        ipfs_hash: String,
-        preview: String,
+        creator: Address,
+        ipfs_hash: &str,
+        preview: &str,
         price: U256,
+        timestamp: U256,
     ) -> U256 {
+        // Validate IPFS hash (CIDv0 Qm..., CIDv1 base32 b... or base36 k...)
+        let cid = match Cid::parse(ipfs_hash) {
+            Ok(cid) => cid,
+            Err(err) => revert(err.message()),
+        };
+
+        assert!(price <= U256::from(u64::MAX), "Price too large");
+
         let article_id = self.article_count.get();
-        let creator = msg::sender();
-        let timestamp = U256::from(block::timestamp());
 
-        // Store article data
-        self.ipfs_hashes.setter(article_id).set_str(&ipfs_hash);
-        self.previews.setter(article_id).set_str(&preview);
-        self.prices.setter(article_id).set(price);
-        self.creators.setter(article_id).set(creator);
-        self.unlocks.setter(article_id).set(U256::from(0));
-        self.timestamps.setter(article_id).set(timestamp);
+        // Store article data (CID as digest + format word instead of a multi-slot string)
+        let mut article = self.packed_articles.setter(article_id);
+        article.creator.set(creator);
+        article.price.set(U64::from(price));
+        article.cid_format.set(U32::from(cid.format()));
+        article.timestamp.set(U64::from(timestamp));
+        article.unlocks.set(U64::ZERO);
+        article.cid_digest.set(FixedBytes::from(cid.digest));
+        self.store_preview(article_id, preview);
+
+        // Index under the creator
+        self.creator_articles.setter(creator).push(article_id);
 
         // Increment count
         self.article_count.set(article_id + U256::from(1));
@@ -73,72 +691,669 @@ impl WikiPayX402 {
         article_id
     }
 
-    /// Get article data
-    /// Returns: (ipfsHash, preview, price, creator, unlocks, timestamp)
-    pub fn get_article(&self, article_id: U256) -> (String, String, U256, Address, U256, U256) {
-        let ipfs_hash = self.ipfs_hashes.getter(article_id).get_string();
-        let preview = self.previews.getter(article_id).get_string();
-        let price = self.prices.get(article_id);
-        let creator = self.creators.get(article_id);
-        let unlocks = self.unlocks.get(article_id);
-        let timestamp = self.timestamps.get(article_id);
-
-        (ipfs_hash, preview, price, creator, unlocks, timestamp)
-    }
-
-    /// Check if nullifier is already used
-    pub fn nullifiers_used(&self, nullifier: FixedBytes<32>) -> bool {
-        self.nullifiers_used.get(nullifier)
-    }
-
-    /// Unlock article using x402 protocol
-    /// Note: USDC transfer is handled by the facilitator off-chain
-    /// This function only records the unlock on-chain
-    /// @param article_id Article to unlock
-    /// @param nullifier Zero-knowledge nullifier (prevents double-spend)
-    /// @param proof Zero-knowledge proof
-    /// @param from User's address
-    /// @param validAfter EIP-3009 validAfter timestamp (for reference)
-    /// @param validBefore EIP-3009 validBefore timestamp (for reference)
-    /// @param nonce EIP-3009 nonce (should match nullifier)
-    /// @param v Signature component
-    /// @param r Signature component
-    /// @param s Signature component
-    pub fn unlock_article_x402(
+    /// Store one imported article under its original id shifted by `id_offset`
+    fn import_article(&mut self, imported: &ImportedArticle, id_offset: U256) {
+        let article_id = imported.article_id.saturating_add(id_offset);
+        assert!(article_id < U256::from(u64::MAX), "Invalid article id");
+        assert!(imported.creator != Address::ZERO, "Invalid creator");
+        assert!(
+            self.creator_of(article_id) == Address::ZERO,
+            "Article already exists"
+        );
+        assert!(imported.price <= U256::from(u64::MAX), "Price too large");
+        assert!(
+            imported.unlocks <= U256::from(u64::MAX),
+            "Unlocks too large"
+        );
+        assert!(
+            imported.timestamp <= U256::from(u64::MAX),
+            "Timestamp too large"
+        );
+
+        // Old deployments stored CIDs unvalidated; keep the ones that do not parse as strings
+        let (format, digest) = match Cid::parse(&imported.ipfs_hash) {
+            Ok(cid) => (cid.format(), FixedBytes::from(cid.digest)),
+            Err(_) => {
+                assert!(!imported.ipfs_hash.is_empty(), "IPFS hash required");
+                self.articles
+                    .setter(article_id)
+                    .ipfs_hash
+                    .set_str(&imported.ipfs_hash);
+                (0, FixedBytes::ZERO)
+            }
+        };
+
+        let mut article = self.packed_articles.setter(article_id);
+        article.creator.set(imported.creator);
+        article.price.set(U64::from(imported.price));
+        article.cid_format.set(U32::from(format));
+        article.timestamp.set(U64::from(imported.timestamp));
+        article.unlocks.set(U64::from(imported.unlocks));
+        article.cid_digest.set(digest);
+
+        // Previews predate the size cap, so inline text is kept as-is
+        if imported.preview.starts_with(IPFS_PREVIEW_PREFIX) {
+            self.store_preview(article_id, &imported.preview);
+        } else {
+            self.articles
+                .setter(article_id)
+                .preview
+                .set_str(&imported.preview);
+        }
+
+        self.creator_articles
+            .setter(imported.creator)
+            .push(article_id);
+
+        if article_id >= self.article_count.get() {
+            self.article_count.set(article_id + U256::from(1));
+        }
+
+        self.emit_indexed::<ArticleImported>(&[imported.article_id.into(), article_id.into()]);
+    }
+
+    /// Store metadata and tags of a freshly published article
+    fn store_details(
         &mut self,
         article_id: U256,
+        metadata: &ArticleMetadata,
+        tags: &[FixedBytes<32>],
+    ) {
+        assert!(metadata.title.len() <= MAX_TITLE_LENGTH, "Title too long");
+        assert!(
+            metadata.language.len() <= MAX_LANGUAGE_LENGTH,
+            "Language code too long"
+        );
+        assert!(
+            metadata.mimeType.len() <= MAX_MIME_TYPE_LENGTH,
+            "MIME type too long"
+        );
+
+        self.titles.setter(article_id).set_str(&metadata.title);
+        self.languages
+            .setter(article_id)
+            .set_str(&metadata.language);
+        self.mime_types
+            .setter(article_id)
+            .set_str(&metadata.mimeType);
+        self.word_counts.setter(article_id).set(metadata.wordCount);
+        self.content_lengths
+            .setter(article_id)
+            .set(metadata.contentLength);
+        self.content_hashes
+            .setter(article_id)
+            .set(metadata.contentHash);
+
+        self.tag_article(article_id, tags);
+    }
+
+    /// Attach tags to a freshly published article and index it under each tag
+    fn tag_article(&mut self, article_id: U256, tags: &[FixedBytes<32>]) {
+        assert!(tags.len() <= MAX_TAGS, "Too many tags");
+
+        for (i, tag) in tags.iter().enumerate() {
+            assert!(!tag.is_zero(), "Invalid tag");
+            assert!(!tags[..i].contains(tag), "Duplicate tag");
+
+            self.article_tags.setter(article_id).push(*tag);
+            self.tag_articles.setter(*tag).push(article_id);
+        }
+    }
+
+    /// Whether an article lives in packed storage (false for legacy and unknown ids)
+    fn is_packed(&self, article_id: U256) -> bool {
+        self.packed_articles.getter(article_id).creator.get() != Address::ZERO
+    }
+
+    /// Creator of an article (address(0) if it does not exist)
+    fn creator_of(&self, article_id: U256) -> Address {
+        let creator = self.packed_articles.getter(article_id).creator.get();
+        if creator != Address::ZERO {
+            return creator;
+        }
+        self.articles.getter(article_id).creator.get()
+    }
+
+    /// USDC price of an article as published or last updated
+    fn price_of(&self, article_id: U256) -> U256 {
+        if self.is_packed(article_id) {
+            return U256::from(self.packed_articles.getter(article_id).price.get());
+        }
+        self.articles.getter(article_id).price.get()
+    }
+
+    /// Publication timestamp of an article
+    fn timestamp_of(&self, article_id: U256) -> U256 {
+        if self.is_packed(article_id) {
+            return U256::from(self.packed_articles.getter(article_id).timestamp.get());
+        }
+        self.articles.getter(article_id).timestamp.get()
+    }
+
+    /// Unlock count of an article
+    fn unlocks_of(&self, article_id: U256) -> U256 {
+        if self.is_packed(article_id) {
+            return U256::from(self.packed_articles.getter(article_id).unlocks.get());
+        }
+        self.articles.getter(article_id).unlocks.get()
+    }
+
+    /// CID string of an article, re-encoded from its binary form
+    fn ipfs_hash(&self, article_id: U256) -> String {
+        let (format, digest) = if self.is_packed(article_id) {
+            let article = self.packed_articles.getter(article_id);
+            (
+                article.cid_format.get().to::<u32>(),
+                article.cid_digest.get(),
+            )
+        } else {
+            (0, FixedBytes::ZERO)
+        };
+
+        if format == 0 {
+            return self.articles.getter(article_id).ipfs_hash.get_string();
+        }
+
+        Cid::from_format(format, digest.0)
+            .map(|cid| cid.encode())
+            .unwrap_or_default()
+    }
+
+    /// Migrate a legacy article before writing to it
+    /// Articles that cannot be packed stay legacy, so callers must check `is_packed`
+    fn ensure_packed(&mut self, article_id: U256) {
+        if !self.is_packed(article_id) {
+            self.migrate_article(article_id);
+        }
+    }
+
+    /// Set an article's unlock count in whichever record holds it
+    /// Does not migrate legacy articles, which keeps the CID codec out of the unlock facets
+    fn set_unlocks(&mut self, article_id: U256, unlocks: U256) {
+        if self.is_packed(article_id) {
+            self.packed_articles
+                .setter(article_id)
+                .unlocks
+                .set(U64::from(unlocks));
+        } else {
+            self.articles.setter(article_id).unlocks.set(unlocks);
+        }
+    }
+
+    /// Move a legacy article from its Solidity `Article` record into packed storage
+    /// Returns false if the id is unknown, already packed, or has a price, timestamp
+    /// or unlock count above u64 (those articles keep using the legacy record)
+    fn migrate_article(&mut self, article_id: U256) -> bool {
+        let creator = self.articles.getter(article_id).creator.get();
+        if creator == Address::ZERO || self.is_packed(article_id) {
+            return false;
+        }
+
+        let price = self.articles.getter(article_id).price.get();
+        let timestamp = self.articles.getter(article_id).timestamp.get();
+        let unlocks = self.articles.getter(article_id).unlocks.get();
+        let max = U256::from(u64::MAX);
+        if price > max || timestamp > max || unlocks > max {
+            return false;
+        }
+
+        // CID strings are converted when they parse; otherwise they stay in `articles`
+        let (format, digest) =
+            match Cid::parse(&self.articles.getter(article_id).ipfs_hash.get_string()) {
+                Ok(cid) => {
+                    self.articles.setter(article_id).ipfs_hash.set_str("");
+                    (cid.format(), FixedBytes::from(cid.digest))
+                }
+                Err(_) => (0, FixedBytes::ZERO),
+            };
+
+        let mut article = self.packed_articles.setter(article_id);
+        article.creator.set(creator);
+        article.price.set(U64::from(price));
+        article.cid_format.set(U32::from(format));
+        article.timestamp.set(U64::from(timestamp));
+        article.unlocks.set(U64::from(unlocks));
+        article.cid_digest.set(digest);
+
+        // Clear legacy slots (gas refund); the preview stays where it is
+        let mut legacy = self.articles.setter(article_id);
+        legacy.price.set(U256::ZERO);
+        legacy.creator.set(Address::ZERO);
+        legacy.unlocks.set(U256::ZERO);
+        legacy.timestamp.set(U256::ZERO);
+
+        // Only Solidity-era articles are still unpacked, and that contract kept no
+        // per-creator index, so this is the first time the article is listed
+        self.creator_articles.setter(creator).push(article_id);
+
+        true
+    }
+
+    /// Largest inline preview accepted, in bytes
+    fn preview_length_cap(&self) -> U256 {
+        let max_length = self.max_preview_length.get();
+        if max_length == U256::ZERO {
+            U256::from(DEFAULT_MAX_PREVIEW_LENGTH)
+        } else {
+            max_length
+        }
+    }
+
+    /// Store a preview: "ipfs://<cid>" as a binary CID, anything else inline up to the cap
+    fn store_preview(&mut self, article_id: U256, preview: &str) {
+        if let Some(cid) = preview.strip_prefix(IPFS_PREVIEW_PREFIX) {
+            let cid = match Cid::parse(cid) {
+                Ok(cid) => cid,
+                Err(err) => revert(err.message()),
+            };
+            self.preview_cid_digests
+                .setter(article_id)
+                .set(FixedBytes::from(cid.digest));
+            self.preview_cid_formats
+                .setter(article_id)
+                .set(U32::from(cid.format()));
+            self.articles.setter(article_id).preview.set_str("");
+            return;
+        }
+
+        assert!(
+            U256::from(preview.len()) <= self.preview_length_cap(),
+            "Preview too long"
+        );
+        self.preview_cid_formats.setter(article_id).set(U32::ZERO);
+        self.articles.setter(article_id).preview.set_str(preview);
+    }
+
+    /// Preview of an article, as inline text or "ipfs://<cid>"
+    fn preview(&self, article_id: U256) -> String {
+        let format = self.preview_cid_formats.get(article_id).to::<u32>();
+        if format == 0 {
+            return self.articles.getter(article_id).preview.get_string();
+        }
+
+        let digest = self.preview_cid_digests.get(article_id);
+        match Cid::from_format(format, digest.0) {
+            Some(cid) => String::from(IPFS_PREVIEW_PREFIX) + &cid.encode(),
+            None => String::new(),
+        }
+    }
+
+    /// Catalog entry for an article
+    fn article_summary(&self, article_id: U256) -> ArticleSummary {
+        (
+            article_id,
+            self.ipfs_hash(article_id),
+            self.preview(article_id),
+            self.price_of(article_id),
+            self.creator_of(article_id),
+            self.unlocks_of(article_id),
+            self.timestamp_of(article_id),
+        )
+    }
+
+    /// Emit an event whose fields are all indexed, given the topics after its signature
+    /// Encoded here because `log` brings in alloy's generic event encoder, which adds
+    /// several KB to every program that emits an event
+    fn emit_indexed<T: SolEvent>(&self, topics: &[FixedBytes<32>]) {
+        let mut bytes = Vec::with_capacity(32 * (topics.len() + 1));
+        bytes.extend_from_slice(T::SIGNATURE_HASH.as_slice());
+        for topic in topics {
+            bytes.extend_from_slice(topic.as_slice());
+        }
+        self.vm().emit_log(&bytes, topics.len() + 1);
+    }
+
+    /// Revert unless the caller is the contract admin
+    fn only_admin(&self) {
+        assert!(self.admin.get() == self.vm().msg_sender(), "Only admin");
+    }
+
+    /// Send tokens or ETH held by the contract, reverting on failure
+    fn transfer_token(&mut self, token: Address, to: Address, amount: U256) {
+        if token == ETH {
+            assert!(
+                self.vm().transfer_eth(to, amount).is_ok(),
+                "Transfer failed"
+            );
+            return;
+        }
+
+        let result = IERC20::new(token).transfer(&mut *self, to, amount);
+        assert!(matches!(result, Ok(true)), "Token transfer failed");
+    }
+
+    /// Reject unknown articles and nullifiers the payer already spent on this article
+    fn check_nullifier(
+        &self,
+        article_id: U256,
+        nullifier: FixedBytes<32>,
+        payer: Address,
+    ) -> Result<(), &'static str> {
+        // Verify nullifier not already used for this article (or before binding existed)
+        let key = self.nullifier_key(nullifier, article_id, payer);
+        if self.nullifiers_used.get(key) || self.nullifiers_used.get(nullifier) {
+            return Err("Nullifier already used");
+        }
+
+        // Get article data (price may have decayed to zero for archive content)
+        if self.creator_of(article_id) == Address::ZERO {
+            return Err("Article does not exist");
+        }
+
+        Ok(())
+    }
+
+    /// Non-reverting unlock validation, returning the reason an unlock would be rejected
+    fn check_unlock(
+        &self,
+        article_id: U256,
+        token: Address,
         nullifier: FixedBytes<32>,
         proof: FixedBytes<32>,
-        from: Address,
-        valid_after: U256,
-        valid_before: U256,
-        nonce: FixedBytes<32>,
+        payer: Address,
+        voucher_code: &str,
+    ) -> Result<(), &'static str> {
+        self.check_nullifier(article_id, nullifier, payer)?;
+
+        // Verify proof is not zero (basic validation)
+        if proof.is_zero() {
+            return Err("Invalid proof");
+        }
+
+        if token != self.usdc_address.get()
+            && self.token_prices.getter(article_id).get(token) == U256::ZERO
+        {
+            return Err("Article not priced in token");
+        }
+
+        if !voucher_code.is_empty() {
+            self.redeemable_voucher(article_id, voucher_code)?;
+        }
+
+        Ok(())
+    }
+
+    /// Amount owed in `token` for an unlock, after decay and voucher discount
+    fn quote_unlock(&self, article_id: U256, token: Address, voucher_code: &str) -> U256 {
+        let usdc_amount = if voucher_code.is_empty() {
+            self.current_price(article_id)
+        } else {
+            let key = self.valid_voucher_key(article_id, voucher_code);
+            self.discounted_price(article_id, key)
+        };
+
+        self.token_amount(article_id, token, usdc_amount)
+    }
+
+    /// Validate and record an unlock, returning the amount owed in `token`
+    /// Consumes the nullifier and voucher, then escrows the amount or credits the creator;
+    /// the caller is responsible for collecting the payment
+    fn record_unlock(
+        &mut self,
+        article_id: U256,
+        token: Address,
+        nullifier: FixedBytes<32>,
+        proof: FixedBytes<32>,
+        payer: Address,
+        voucher_code: &str,
+    ) -> U256 {
+        if let Err(reason) =
+            self.check_unlock(article_id, token, nullifier, proof, payer, voucher_code)
+        {
+            revert(reason);
+        }
+
+        let amount = self.quote_unlock(article_id, token, voucher_code);
+
+        // Consume one voucher use, remembered so a refund can give it back
+        if !voucher_code.is_empty() {
+            let key = self.valid_voucher_key(article_id, voucher_code);
+            let uses_left = self.voucher_uses_left.get(key);
+            self.voucher_uses_left
+                .setter(key)
+                .set(uses_left - U256::from(1));
+            self.unlock_vouchers
+                .setter(self.nullifier_key(nullifier, article_id, payer))
+                .set(key);
+        }
+
+        self.settle_unlock(article_id, token, nullifier, payer, amount);
+        amount
+    }
+
+    /// Consume a validated nullifier, count the unlock and escrow or credit its payment
+    /// A zero payer records an unlock nobody can refund (paid from the membership pool)
+    fn settle_unlock(
+        &mut self,
+        article_id: U256,
+        token: Address,
+        nullifier: FixedBytes<32>,
+        payer: Address,
+        amount: U256,
+    ) {
+        let creator = self.creator_of(article_id);
+
+        // Mark nullifier as used (bound to this article, payer, chain and contract)
+        let key = self.nullifier_key(nullifier, article_id, payer);
+        self.nullifiers_used.setter(key).set(true);
+        self.unlock_amounts.setter(key).set(amount);
+        self.unlock_articles.setter(key).set(article_id);
+        self.unlock_payers.setter(key).set(payer);
+        self.unlock_tokens.setter(key).set(token);
+
+        // Increment unlock count
+        let unlocks = self.unlocks_of(article_id);
+        self.set_unlocks(article_id, unlocks + U256::from(1));
+
+        if amount > U256::ZERO {
+            // Hold payment in escrow, or credit the creator right away if refunds are disabled
+            // or there is no payer to refund (membership unlocks)
+            let window = self.refund_windows.get(creator);
+            if window > U256::ZERO && payer != Address::ZERO {
+                let now = U256::from(self.vm().block_timestamp());
+                self.escrow_amounts.setter(key).set(amount);
+                self.escrow_releases.setter(key).set(now + window);
+            } else {
+                self.credit_earnings(creator, token, amount);
+            }
+        }
+    }
+
+    /// EIP-712 domain separator of this deployment (see eip712::domain_separator)
+    fn domain_separator_hash(&self) -> FixedBytes<32> {
+        eip712::domain_separator(self.vm().chain_id(), self.vm().contract_address())
+    }
+
+    /// Recover the creator of a signed publish request and consume their nonce
+    fn consume_publish_signature(
+        &mut self,
+        digest: FixedBytes<32>,
+        nonce: U256,
         v: u8,
         r: FixedBytes<32>,
         s: FixedBytes<32>,
-    ) -> bool {
-        // Verify nullifier not already used
-        assert!(!self.nullifiers_used.get(nullifier), "Nullifier already used");
+    ) -> Address {
+        let creator = self.recover_signer(digest, v, r, s);
+        assert!(creator != Address::ZERO, "Invalid signature");
+        assert!(nonce == self.publish_nonces.get(creator), "Invalid nonce");
+        self.publish_nonces
+            .setter(creator)
+            .set(nonce + U256::from(1));
 
-        // Get article data
-        let price = self.prices.get(article_id);
+        creator
+    }
 
-        assert!(price > U256::ZERO, "Article does not exist");
+    /// Recover the signer of a digest through the ecrecover precompile
+    /// Returns address(0) for invalid signatures
+    fn recover_signer(
+        &self,
+        digest: FixedBytes<32>,
+        v: u8,
+        r: FixedBytes<32>,
+        s: FixedBytes<32>,
+    ) -> Address {
+        let mut input = [0u8; 128];
+        input[..32].copy_from_slice(digest.as_slice());
+        input[63] = v;
+        input[64..96].copy_from_slice(r.as_slice());
+        input[96..].copy_from_slice(s.as_slice());
 
-        // Verify proof is not zero (basic validation)
-        assert!(!proof.is_zero(), "Invalid proof");
+        match self.vm().static_call(&self, ECRECOVER, &input) {
+            Ok(output) if output.len() == 32 => Address::from_slice(&output[12..]),
+            _ => Address::ZERO,
+        }
+    }
 
-        // Mark nullifier as used
-        self.nullifiers_used.setter(nullifier).set(true);
+    /// Revert unless the caller is the creator or a delegate holding `permission`
+    fn authorize(&self, creator: Address, permission: u8) {
+        let sender = self.vm().msg_sender();
+        if sender == creator {
+            return;
+        }
 
-        // Increment unlock count
-        let current_unlocks = self.unlocks.get(article_id);
-        self.unlocks.setter(article_id).set(current_unlocks + U256::from(1));
+        let granted = self
+            .delegate_permissions
+            .getter(creator)
+            .get(sender)
+            .to::<u8>();
+        assert!(granted & permission != 0, "Not authorized by creator");
+    }
 
-        // Note: USDC transfer is handled by facilitator who calls this function
-        // The facilitator has already executed transferWithAuthorization on USDC contract
-        // before calling this function
+    /// Revert unless the article exists and the caller may act on it with `permission`
+    fn authorize_article(&self, article_id: U256, permission: u8) {
+        let creator = self.creator_of(article_id);
+        assert!(creator != Address::ZERO, "Article does not exist");
+        self.authorize(creator, permission);
+    }
 
-        true
+    /// Address that receives a creator's earnings (the creator unless a payout address is set)
+    fn payout_of(&self, creator: Address) -> Address {
+        let payout = self.payout_addresses.get(creator);
+        if payout == Address::ZERO {
+            creator
+        } else {
+            payout
+        }
+    }
+
+    /// Add to the withdrawable balance of a creator's payout address in a token
+    fn credit_earnings(&mut self, creator: Address, token: Address, amount: U256) {
+        let payout = self.payout_of(creator);
+        let current_earnings = self.creator_earnings.getter(payout).get(token);
+        self.creator_earnings
+            .setter(payout)
+            .setter(token)
+            .set(current_earnings + amount);
+    }
+
+    /// Convert a USDC-denominated amount for an article into a payment token amount
+    /// Uses the creator's token price as the exchange ratio against the USDC price. Articles
+    /// without a USDC price have no ratio, so their token price is charged as set
+    fn token_amount(&self, article_id: U256, token: Address, usdc_amount: U256) -> U256 {
+        if token == self.usdc_address.get() {
+            return usdc_amount;
+        }
+
+        let token_price = self.token_prices.getter(article_id).get(token);
+        assert!(token_price > U256::ZERO, "Article not priced in token");
+
+        let usdc_price = self.price_of(article_id);
+        if usdc_price == U256::ZERO {
+            return token_price;
+        }
+
+        token_price * usdc_amount / usdc_price
+    }
+
+    /// Effective price of an article at the current block timestamp (see pricing::decayed_price)
+    fn current_price(&self, article_id: U256) -> U256 {
+        pricing::decayed_price(
+            self.price_of(article_id),
+            self.timestamp_of(article_id),
+            U256::from(self.vm().block_timestamp()),
+            self.decay_half_lives.get(article_id),
+            self.price_floors.get(article_id),
+            self.free_after.get(article_id),
+        )
+    }
+
+    /// Storage key of a voucher, scoped to its creator so codes cannot be squatted
+    fn voucher_key(creator: Address, code_hash: FixedBytes<32>) -> FixedBytes<32> {
+        let mut preimage = Vec::with_capacity(52);
+        preimage.extend_from_slice(creator.as_slice());
+        preimage.extend_from_slice(code_hash.as_slice());
+        crypto::keccak(preimage)
+    }
+
+    /// EIP-3009 nonce for the next unlock under a nullifier key (see get_unlock_nonce)
+    fn unlock_nonce(&self, nullifier_key: FixedBytes<32>) -> FixedBytes<32> {
+        nullifier::unlock_nonce(nullifier_key, self.unlock_refunds.get(nullifier_key))
+    }
+
+    /// Bind a nullifier to an article and payer on this chain and contract, so a nullifier
+    /// seen in the mempool cannot be replayed against another article, reader or deployment
+    fn nullifier_key(
+        &self,
+        nullifier: FixedBytes<32>,
+        article_id: U256,
+        payer: Address,
+    ) -> FixedBytes<32> {
+        nullifier::key(
+            nullifier,
+            article_id,
+            payer,
+            self.vm().chain_id(),
+            self.vm().contract_address(),
+        )
+    }
+
+    /// Resolve a voucher code for an article, reverting unless it can be redeemed
+    fn valid_voucher_key(&self, article_id: U256, code: &str) -> FixedBytes<32> {
+        match self.redeemable_voucher(article_id, code) {
+            Ok(key) => key,
+            Err(reason) => revert(reason),
+        }
+    }
+
+    /// Non-reverting voucher lookup, returning the reason a code cannot be redeemed
+    fn redeemable_voucher(
+        &self,
+        article_id: U256,
+        code: &str,
+    ) -> Result<FixedBytes<32>, &'static str> {
+        let creator = self.creator_of(article_id);
+        if creator == Address::ZERO {
+            return Err("Article does not exist");
+        }
+
+        let key = Self::voucher_key(creator, crypto::keccak(code.as_bytes()));
+        if self.voucher_creators.get(key) != creator {
+            return Err("Invalid voucher");
+        }
+
+        let scope = self.voucher_articles.get(key);
+        if scope != ALL_ARTICLES && scope != article_id {
+            return Err("Voucher not valid for article");
+        }
+
+        let expiry = self.voucher_expiries.get(key);
+        let now = U256::from(self.vm().block_timestamp());
+        if expiry != U256::ZERO && now >= expiry {
+            return Err("Voucher expired");
+        }
+        if self.voucher_uses_left.get(key) == U256::ZERO {
+            return Err("Voucher exhausted");
+        }
+
+        Ok(key)
+    }
+
+    /// Current price of an article with a voucher's percentage and fixed discounts applied
+    fn discounted_price(&self, article_id: U256, key: FixedBytes<32>) -> U256 {
+        pricing::discounted_price(
+            self.current_price(article_id),
+            self.voucher_percent_off.get(key),
+            self.voucher_amount_off.get(key),
+        )
     }
 }
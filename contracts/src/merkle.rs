@@ -0,0 +1,214 @@
+//! Incremental Merkle tree parameters for reader identity commitments
+//!
+//! Fixed-depth binary tree hashed with Poseidon, built like the tree of a Semaphore v3 group:
+//! empty leaves hold the group's zero value `hash(groupId)` (see GROUP_ID), as in
+//! `SemaphoreGroups._createGroup` and `new Group(groupId, depth)` of @semaphore-protocol/group,
+//! so Merkle proofs from a standard `Group` of the registered members verify. Leaves are
+//! filled left to right; only one side node per level is stored, so an insert costs DEPTH
+//! hashes.
+
+use crate::poseidon;
+use stylus_sdk::alloy_primitives::{uint, U256};
+use stylus_sdk::storage::{StorageMap, StorageU256};
+
+/// Tree depth (up to 2^20 identities); also passed to the Semaphore verifier
+pub const DEPTH: usize = 20;
+
+/// Number of recent roots accepted in proofs, so members can prove against a root that
+/// was current when they generated the proof
+pub const ROOT_HISTORY_SIZE: u64 = 30;
+
+/// Semaphore group id of the reader group; clients rebuild it as `new Group(0, 20, members)`
+/// with the members in join order
+pub const GROUP_ID: U256 = U256::ZERO;
+
+/// Roots of empty subtrees: ZEROS[i] = hash2(ZEROS[i - 1], ZEROS[i - 1]), and the zero leaf
+/// ZEROS[0] = uint256(keccak256(abi.encodePacked(GROUP_ID))) >> 8, Semaphore v3's `hash`
+/// ZEROS[DEPTH] is the root of the empty tree
+pub const ZEROS: [U256; DEPTH + 1] = [
+    uint!(0x00290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e5_U256),
+    uint!(0x19a50a8fa680b962430214333283f69e45841f32788f518810800ed6776c191e_U256),
+    uint!(0x027e2776bf89e6778c640b39a9385e0d8b891158992cb738ea7a32924d858911_U256),
+    uint!(0x05feb1ff2128bbcac09bb96fcb639e64d54c46c74622ea82314f3d53da097b8c_U256),
+    uint!(0x03f4c21b6364b7c36f3310515e1604c0d922feeb978fc5df85321ab1573e837a_U256),
+    uint!(0x0149f2335d21540f953620f7fbe9bdf525bef2ede3845869a9b7d47dc09c832c_U256),
+    uint!(0x1a1aee698b7d47ce995f725b38c23ab43ad01696ca49233e716c2ee6285d5773_U256),
+    uint!(0x05ca179250fbf4db1fbe53d7df8c976d6e5414c7efb6d13bc38eb39eeffb7e28_U256),
+    uint!(0x302c13b484a0910c0791dc98daa7be3e94e9a7c49926724668cfe354bb8b33f4_U256),
+    uint!(0x2ccefafae41bc8620c81f13a0d40eef0532b528c32a4f470419f148f561f50b5_U256),
+    uint!(0x2f13a97016a0f77c2ff2df94d4b63c716e8a67105859a42a02a363e1db9adcfe_U256),
+    uint!(0x03878e877e9c5fbad75c04c1e290ae748b91f8c16cefd62ea16aad211e431e7c_U256),
+    uint!(0x0f55d5c49f85c6dffdf7dc0b7e977b97ad7efbc3d5e86403dd631481d4f9296d_U256),
+    uint!(0x298f54f9a206ab9425c7494b896c00e1028f7c3f7cb80f9711c385e2d301d8b2_U256),
+    uint!(0x0bd1e3c1e8fab56643950779a8e24f1ee912cd050980760cf3aeaf10324b2533_U256),
+    uint!(0x29dfb38b1ea22a099975610297e0cbb7f0ff85aca65eb83da0d285d92588a999_U256),
+    uint!(0x22e3555bf26debed87c38a468cda118b851cb04a48177024ebe863621ac7ea7a_U256),
+    uint!(0x04e727e0277a0f77c26ea113729ffa32b76c0c95b26c4a2ef19f83f1168286f8_U256),
+    uint!(0x1e36d04e810c36ce7fb32a742a95cbde72a648f613f68d251759158de81fd5ba_U256),
+    uint!(0x1730d9d72336090dc65ea2867bf8f6f8e0c1342eab1648d3f33dd152c81ce8b1_U256),
+    uint!(0x0513c7c90a40b1b0d26da6f7482390943806fa0389d37529235a7c27a964653e_U256),
+];
+
+/// Hash of two child nodes
+pub fn hash_nodes(left: U256, right: U256) -> U256 {
+    poseidon::hash2(left, right)
+}
+
+/// Last left node of each level, the only state an append-only tree needs
+pub trait SideNodes {
+    fn side_node(&self, level: usize) -> U256;
+    fn set_side_node(&mut self, level: usize, node: U256);
+}
+
+/// Side nodes kept in contract storage, keyed by level
+impl SideNodes for StorageMap<U256, StorageU256> {
+    fn side_node(&self, level: usize) -> U256 {
+        self.get(U256::from(level))
+    }
+
+    fn set_side_node(&mut self, level: usize, node: U256) {
+        self.setter(U256::from(level)).set(node);
+    }
+}
+
+/// Append a leaf at `index` (the current leaf count) and return the new root
+/// Reads the side nodes of the levels where the new leaf is a right child and records
+/// it as the side node where it is a left child
+/// @param side_nodes Side nodes left by the previous inserts
+/// @param index Position of the new leaf; must be below 2^DEPTH
+/// @param leaf Leaf value
+pub fn insert(side_nodes: &mut impl SideNodes, index: U256, leaf: U256) -> U256 {
+    let mut node = leaf;
+    let mut position = index;
+    for (level, zero) in ZEROS.iter().take(DEPTH).enumerate() {
+        if position.bit(0) {
+            node = hash_nodes(side_nodes.side_node(level), node);
+        } else {
+            side_nodes.set_side_node(level, node);
+            node = hash_nodes(node, *zero);
+        }
+        position >>= 1;
+    }
+    node
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_leaf_is_the_semaphore_hash_of_the_group_id() {
+        assert_eq!(ZEROS[0], crate::semaphore::field_hash(GROUP_ID));
+    }
+
+    #[test]
+    fn zeros_are_hashes_of_the_level_below() {
+        for level in 1..=DEPTH {
+            assert_eq!(
+                ZEROS[level],
+                poseidon::hash2(ZEROS[level - 1], ZEROS[level - 1]),
+                "ZEROS[{}]",
+                level
+            );
+        }
+    }
+
+    #[test]
+    fn hash_nodes_is_poseidon() {
+        let (left, right) = (U256::from(1), U256::from(2));
+        assert_eq!(hash_nodes(left, right), poseidon::hash2(left, right));
+    }
+
+    impl SideNodes for [U256; DEPTH] {
+        fn side_node(&self, level: usize) -> U256 {
+            self[level]
+        }
+
+        fn set_side_node(&mut self, level: usize, node: U256) {
+            self[level] = node;
+        }
+    }
+
+    /// Root recomputed from every leaf, padding missing subtrees with ZEROS
+    fn full_root(leaves: &[U256]) -> U256 {
+        let mut nodes = leaves.to_vec();
+        for zero in ZEROS.iter().take(DEPTH) {
+            if nodes.len() % 2 == 1 {
+                nodes.push(*zero);
+            }
+            nodes = nodes
+                .chunks(2)
+                .map(|pair| hash_nodes(pair[0], pair[1]))
+                .collect();
+        }
+        nodes.first().copied().unwrap_or(ZEROS[DEPTH])
+    }
+
+    // Roots of `new Group(0, 20, members)` (@semaphore-protocol/group v3), recomputed from
+    // that construction with this crate's Poseidon (which matches circomlibjs); compare with
+    // `new Group(0, 20, [1n]).root` and `new Group(0, 20).root`
+    #[test]
+    fn one_member_group_root_vector() {
+        let mut side_nodes = [U256::ZERO; DEPTH];
+        assert_eq!(
+            insert(&mut side_nodes, U256::ZERO, U256::from(1)),
+            uint!(0x30456b59fcaf5a45cf54ee9a5040659e86986a9412a6e677af380c946fc0ad6c_U256)
+        );
+    }
+
+    #[test]
+    fn empty_group_root_vector() {
+        assert_eq!(
+            ZEROS[DEPTH],
+            uint!(0x0513c7c90a40b1b0d26da6f7482390943806fa0389d37529235a7c27a964653e_U256)
+        );
+    }
+
+    #[test]
+    fn empty_tree_root_is_the_top_zero() {
+        assert_eq!(full_root(&[]), ZEROS[DEPTH]);
+    }
+
+    #[test]
+    fn first_leaf_hashes_against_zero_subtrees() {
+        let mut side_nodes = [U256::ZERO; DEPTH];
+        let leaf = U256::from(42);
+
+        let mut expected = leaf;
+        for zero in ZEROS.iter().take(DEPTH) {
+            expected = hash_nodes(expected, *zero);
+        }
+
+        assert_eq!(insert(&mut side_nodes, U256::ZERO, leaf), expected);
+        assert_eq!(side_nodes[0], leaf);
+    }
+
+    #[test]
+    fn incremental_roots_match_full_recomputation() {
+        let mut side_nodes = [U256::ZERO; DEPTH];
+        let mut leaves = Vec::new();
+
+        // Covers left and right children at the low levels, including a full subtree of 8
+        for i in 0..9u64 {
+            let leaf = U256::from(1000 + i);
+            leaves.push(leaf);
+            let root = insert(&mut side_nodes, U256::from(i), leaf);
+            assert_eq!(root, full_root(&leaves), "root after leaf {}", i);
+        }
+    }
+
+    #[test]
+    fn last_leaf_of_the_tree_is_a_right_child_on_every_level() {
+        let mut side_nodes = [U256::from(7); DEPTH];
+        let index = U256::from((1u64 << DEPTH) - 1);
+        let leaf = U256::from(9);
+
+        let mut expected = leaf;
+        for _ in 0..DEPTH {
+            expected = hash_nodes(U256::from(7), expected);
+        }
+
+        assert_eq!(insert(&mut side_nodes, index, leaf), expected);
+        assert_eq!(side_nodes, [U256::from(7); DEPTH]);
+    }
+}
@@ -0,0 +1,135 @@
+//! Storage keys and EIP-3009 nonces of paid unlocks
+//!
+//! A reader's nullifier is derived from public data (see the frontend's
+//! `generateDeterministicNullifier`), so it is not a secret: the key an unlock is recorded
+//! under also binds the account paying for it. Anyone else submitting the same nullifier
+//! for the same article lands on their own key and pays for their own unlock.
+
+use alloc::vec::Vec;
+use stylus_sdk::alloy_primitives::{Address, FixedBytes, U256};
+use stylus_sdk::crypto;
+
+/// Key an unlock is recorded under
+/// keccak256(abi.encode(nullifier, articleId, payer, chainId, contract))
+/// @param payer Account paying for the unlock (address(0) for membership unlocks)
+pub fn key(
+    nullifier: FixedBytes<32>,
+    article_id: U256,
+    payer: Address,
+    chain_id: u64,
+    contract: Address,
+) -> FixedBytes<32> {
+    let mut preimage = Vec::with_capacity(160);
+    preimage.extend_from_slice(nullifier.as_slice());
+    preimage.extend_from_slice(&article_id.to_be_bytes::<32>());
+    preimage.extend_from_slice(payer.into_word().as_slice());
+    preimage.extend_from_slice(&U256::from(chain_id).to_be_bytes::<32>());
+    preimage.extend_from_slice(contract.into_word().as_slice());
+    crypto::keccak(preimage)
+}
+
+/// EIP-3009 nonce the next unlock under a key must sign
+/// The key itself until the unlock is first refunded, then
+/// keccak256(abi.encode(key, refunds)), since the token keeps the old nonce spent
+pub fn unlock_nonce(key: FixedBytes<32>, refunds: U256) -> FixedBytes<32> {
+    if refunds == U256::ZERO {
+        return key;
+    }
+    let mut preimage = Vec::with_capacity(64);
+    preimage.extend_from_slice(key.as_slice());
+    preimage.extend_from_slice(&refunds.to_be_bytes::<32>());
+    crypto::keccak(preimage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stylus_sdk::alloy_primitives::{address, b256};
+
+    const READER: Address = address!("3333333333333333333333333333333333333333");
+    const OTHER: Address = address!("4444444444444444444444444444444444444444");
+    const CONTRACT: Address = address!("2222222222222222222222222222222222222222");
+
+    fn reader_key() -> FixedBytes<32> {
+        key(
+            FixedBytes::repeat_byte(0xab),
+            U256::from(7),
+            READER,
+            42161,
+            CONTRACT,
+        )
+    }
+
+    #[test]
+    fn key_vector() {
+        assert_eq!(
+            reader_key(),
+            b256!("538c9f0493f381cab429ed3ef223e4a4a40c5346989ad316aac0aa358ae8d10f")
+        );
+    }
+
+    #[test]
+    fn key_binds_the_payer() {
+        let other = key(
+            FixedBytes::repeat_byte(0xab),
+            U256::from(7),
+            OTHER,
+            42161,
+            CONTRACT,
+        );
+        assert_eq!(
+            other,
+            b256!("2b92f3bfc51c32837882c48e7929f94eaa86f063b1505d9408dbe5e36a4b618c")
+        );
+        assert_ne!(other, reader_key());
+    }
+
+    #[test]
+    fn key_binds_article_chain_and_contract() {
+        let nullifier = FixedBytes::repeat_byte(0xab);
+        assert_ne!(
+            key(nullifier, U256::from(8), READER, 42161, CONTRACT),
+            reader_key()
+        );
+        assert_ne!(
+            key(nullifier, U256::from(7), READER, 421614, CONTRACT),
+            reader_key()
+        );
+        assert_ne!(
+            key(nullifier, U256::from(7), READER, 42161, OTHER),
+            reader_key()
+        );
+    }
+
+    #[test]
+    fn nonce_is_the_key_until_refunded() {
+        assert_eq!(unlock_nonce(reader_key(), U256::ZERO), reader_key());
+        assert_eq!(
+            unlock_nonce(reader_key(), U256::from(1)),
+            b256!("c628fd1118caea2309ff2733bb611d985be058558c2fbcaa1926d01a0378797b")
+        );
+        assert_ne!(
+            unlock_nonce(reader_key(), U256::from(1)),
+            unlock_nonce(reader_key(), U256::from(2))
+        );
+    }
+
+    #[test]
+    fn refunded_key_cannot_be_reused_by_another_account() {
+        // The reader's unlock was refunded once, freeing their key under a rotated nonce
+        let reader_nonce = unlock_nonce(reader_key(), U256::from(1));
+
+        // Another account replaying the reader's (public) nullifier gets its own key and
+        // nonce, so it neither reoccupies the reader's key nor spends the reader's nonce
+        let other = key(
+            FixedBytes::repeat_byte(0xab),
+            U256::from(7),
+            OTHER,
+            42161,
+            CONTRACT,
+        );
+        assert_ne!(other, reader_key());
+        assert_ne!(unlock_nonce(other, U256::ZERO), reader_nonce);
+        assert_ne!(unlock_nonce(other, U256::from(1)), reader_nonce);
+    }
+}
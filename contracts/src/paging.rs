@@ -0,0 +1,65 @@
+//! Offset/limit pagination for the catalog views
+
+use stylus_sdk::alloy_primitives::U256;
+
+/// Largest page returned by the catalog view functions
+pub const MAX_PAGE_SIZE: u64 = 100;
+
+/// Clamp a requested page to `[0, total)`, returning the index range to read
+/// Offsets past the end give an empty range; limits above MAX_PAGE_SIZE are capped
+/// @param total Number of entries in the list
+/// @param offset Index of the first entry requested
+/// @param limit Number of entries requested
+pub fn page_bounds(total: U256, offset: U256, limit: U256) -> (usize, usize) {
+    let total = total.saturating_to::<usize>();
+    let start = offset.saturating_to::<usize>().min(total);
+    let limit = limit.min(U256::from(MAX_PAGE_SIZE)).to::<usize>();
+
+    (start, start.saturating_add(limit).min(total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bounds(total: u64, offset: u64, limit: u64) -> (usize, usize) {
+        page_bounds(U256::from(total), U256::from(offset), U256::from(limit))
+    }
+
+    #[test]
+    fn returns_the_requested_window() {
+        assert_eq!(bounds(50, 0, 10), (0, 10));
+        assert_eq!(bounds(50, 10, 10), (10, 20));
+    }
+
+    #[test]
+    fn truncates_the_last_page() {
+        assert_eq!(bounds(50, 45, 10), (45, 50));
+        assert_eq!(bounds(50, 49, 10), (49, 50));
+    }
+
+    #[test]
+    fn offsets_at_or_past_the_end_are_empty() {
+        assert_eq!(bounds(50, 50, 10), (50, 50));
+        assert_eq!(bounds(50, 51, 10), (50, 50));
+        assert_eq!(bounds(0, 0, 10), (0, 0));
+        assert_eq!(
+            page_bounds(U256::from(50), U256::MAX, U256::from(10)),
+            (50, 50)
+        );
+    }
+
+    #[test]
+    fn limit_is_capped_at_max_page_size() {
+        assert_eq!(bounds(500, 0, 1000), (0, MAX_PAGE_SIZE as usize));
+        assert_eq!(
+            page_bounds(U256::from(500), U256::from(400), U256::MAX),
+            (400, 500)
+        );
+    }
+
+    #[test]
+    fn zero_limit_is_empty() {
+        assert_eq!(bounds(50, 10, 0), (10, 10));
+    }
+}
@@ -0,0 +1,173 @@
+//! Article price math: archive decay and voucher discounts
+//!
+//! Pure functions over USDC amounts (6 decimals); the contract loads the schedule and
+//! voucher terms from storage and passes them in.
+
+use stylus_sdk::alloy_primitives::U256;
+
+/// Price of an article at `now` under its decay schedule
+/// Halves once per elapsed half-life since publication, clamped to the floor,
+/// and drops to zero once the free-after date has passed
+/// @param price Publish price
+/// @param published Publication timestamp
+/// @param now Current block timestamp
+/// @param half_life Seconds per halving (0 = no decay)
+/// @param floor Amount the price never decays below
+/// @param free_after Timestamp from which the article is free (0 = never)
+pub fn decayed_price(
+    price: U256,
+    published: U256,
+    now: U256,
+    half_life: U256,
+    floor: U256,
+    free_after: U256,
+) -> U256 {
+    if free_after != U256::ZERO && now >= free_after {
+        return U256::ZERO;
+    }
+    if half_life == U256::ZERO {
+        return price;
+    }
+
+    let halvings = now.saturating_sub(published) / half_life;
+    let decayed = if halvings >= U256::from(256) {
+        U256::ZERO
+    } else {
+        price >> halvings.to::<usize>()
+    };
+
+    decayed.max(floor)
+}
+
+/// Price with a voucher's percentage discount and then its fixed discount applied,
+/// saturating at zero
+/// @param price Price before the voucher
+/// @param percent_off Percentage discount (0-100)
+/// @param amount_off Fixed discount in the same unit as `price`
+pub fn discounted_price(price: U256, percent_off: U256, amount_off: U256) -> U256 {
+    let percent = price * percent_off / U256::from(100);
+
+    price.saturating_sub(percent).saturating_sub(amount_off)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRICE: u64 = 1_000_000;
+    const PUBLISHED: u64 = 1_700_000_000;
+    const DAY: u64 = 24 * 60 * 60;
+
+    fn decay(age: u64, half_life: u64, floor: u64, free_after: u64) -> u64 {
+        decayed_price(
+            U256::from(PRICE),
+            U256::from(PUBLISHED),
+            U256::from(PUBLISHED + age),
+            U256::from(half_life),
+            U256::from(floor),
+            U256::from(free_after),
+        )
+        .to::<u64>()
+    }
+
+    #[test]
+    fn no_half_life_keeps_the_price() {
+        assert_eq!(decay(1000 * DAY, 0, 0, 0), PRICE);
+    }
+
+    #[test]
+    fn halves_exactly_at_each_half_life() {
+        assert_eq!(decay(0, DAY, 0, 0), PRICE);
+        assert_eq!(decay(DAY - 1, DAY, 0, 0), PRICE);
+        assert_eq!(decay(DAY, DAY, 0, 0), PRICE / 2);
+        assert_eq!(decay(2 * DAY - 1, DAY, 0, 0), PRICE / 2);
+        assert_eq!(decay(2 * DAY, DAY, 0, 0), PRICE / 4);
+        assert_eq!(decay(10 * DAY, DAY, 0, 0), PRICE >> 10);
+    }
+
+    #[test]
+    fn decays_to_zero_after_many_halvings() {
+        assert_eq!(decay(20 * DAY, DAY, 0, 0), 0);
+        assert_eq!(decay(255, 1, 0, 0), 0);
+        assert_eq!(decay(256, 1, 0, 0), 0);
+        assert_eq!(decay(u32::MAX as u64, 1, 0, 0), 0);
+    }
+
+    #[test]
+    fn huge_prices_decay_without_overflow() {
+        let price = decayed_price(
+            U256::MAX,
+            U256::ZERO,
+            U256::from(255),
+            U256::from(1),
+            U256::ZERO,
+            U256::ZERO,
+        );
+        assert_eq!(price, U256::from(1));
+    }
+
+    #[test]
+    fn floor_applies_only_once_the_price_decays_below_it() {
+        assert_eq!(decay(0, DAY, 300_000, 0), PRICE);
+        assert_eq!(decay(DAY, DAY, 300_000, 0), PRICE / 2);
+        assert_eq!(decay(2 * DAY, DAY, 300_000, 0), 300_000);
+        assert_eq!(decay(300 * DAY, DAY, 300_000, 0), 300_000);
+    }
+
+    #[test]
+    fn floor_is_ignored_without_decay() {
+        assert_eq!(decay(300 * DAY, 0, 2 * PRICE, 0), PRICE);
+    }
+
+    #[test]
+    fn free_after_overrides_price_and_floor() {
+        let free_after = PUBLISHED + 7 * DAY;
+        assert_eq!(decay(7 * DAY - 1, 0, 0, free_after), PRICE);
+        assert_eq!(decay(7 * DAY, 0, 0, free_after), 0);
+        assert_eq!(decay(8 * DAY, DAY, 300_000, free_after), 0);
+    }
+
+    #[test]
+    fn timestamp_before_publication_is_age_zero() {
+        let price = decayed_price(
+            U256::from(PRICE),
+            U256::from(PUBLISHED),
+            U256::from(PUBLISHED - DAY),
+            U256::from(DAY),
+            U256::ZERO,
+            U256::ZERO,
+        );
+        assert_eq!(price, U256::from(PRICE));
+    }
+
+    fn discount(price: u64, percent_off: u64, amount_off: u64) -> u64 {
+        discounted_price(
+            U256::from(price),
+            U256::from(percent_off),
+            U256::from(amount_off),
+        )
+        .to::<u64>()
+    }
+
+    #[test]
+    fn applies_percent_then_fixed_discount() {
+        assert_eq!(discount(PRICE, 0, 0), PRICE);
+        assert_eq!(discount(PRICE, 25, 0), 750_000);
+        assert_eq!(discount(PRICE, 0, 100_000), 900_000);
+        assert_eq!(discount(PRICE, 25, 100_000), 650_000);
+    }
+
+    #[test]
+    fn percent_discount_rounds_down() {
+        assert_eq!(discount(3, 50, 0), 2);
+        assert_eq!(discount(PRICE, 100, 0), 0);
+    }
+
+    #[test]
+    fn discounts_saturate_at_zero() {
+        assert_eq!(discount(PRICE, 0, PRICE), 0);
+        assert_eq!(discount(PRICE, 0, 2 * PRICE), 0);
+        assert_eq!(discount(PRICE, 90, PRICE), 0);
+        assert_eq!(discount(0, 50, 10), 0);
+    }
+}
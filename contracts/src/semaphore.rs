@@ -0,0 +1,85 @@
+//! Public inputs of member unlock proofs, in the form the Semaphore v3 verifier takes them
+//!
+//! `SemaphoreVerifier` v3 (`verifyProof(root, nullifierHash, signal, externalNullifier,
+//! proof, depth)`) reduces `signal` and `externalNullifier` to field elements itself with
+//! `uint256(keccak256(abi.encodePacked(x))) >> 8`, and `generateProof` in
+//! `@semaphore-protocol/proof` v3 applies the same hash to the values it is given. The
+//! contract and the client both pass the raw values computed here; neither hashes them first.
+
+use alloc::vec::Vec;
+use stylus_sdk::alloy_primitives::{Address, U256};
+use stylus_sdk::crypto;
+
+/// Signal of a member unlock: keccak256(abi.encode(sender, articleId))
+/// Binds the proof to the account submitting it and to the article
+pub fn signal(sender: Address, article_id: U256) -> U256 {
+    let mut preimage = Vec::with_capacity(64);
+    preimage.extend_from_slice(sender.into_word().as_slice());
+    preimage.extend_from_slice(&article_id.to_be_bytes::<32>());
+    U256::from_be_bytes(crypto::keccak(preimage).0)
+}
+
+/// External nullifier (scope) of an unlock slot: keccak256(abi.encode(slot, chainId, contract))
+/// Each identity gets one nullifier per slot and deployment
+pub fn scope(slot: U256, chain_id: u64, contract: Address) -> U256 {
+    let mut preimage = Vec::with_capacity(96);
+    preimage.extend_from_slice(&slot.to_be_bytes::<32>());
+    preimage.extend_from_slice(&U256::from(chain_id).to_be_bytes::<32>());
+    preimage.extend_from_slice(contract.into_word().as_slice());
+    U256::from_be_bytes(crypto::keccak(preimage).0)
+}
+
+/// Field element the verifier derives from a signal or scope before checking the proof
+/// uint256(keccak256(abi.encodePacked(value))) >> 8, Semaphore v3's `hash`
+pub fn field_hash(value: U256) -> U256 {
+    U256::from_be_bytes(crypto::keccak(value.to_be_bytes::<32>()).0) >> 8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stylus_sdk::alloy_primitives::{address, uint};
+
+    const SENDER: Address = address!("1111111111111111111111111111111111111111");
+    const CONTRACT: Address = address!("2222222222222222222222222222222222222222");
+
+    #[test]
+    fn signal_vector() {
+        let signal = signal(SENDER, U256::from(7));
+        assert_eq!(
+            signal,
+            uint!(0x07315875c131dc1dff59b5eecd3feba7c4eb34f9c8bac4a22e69acd1d04d63c5_U256)
+        );
+        assert_eq!(
+            field_hash(signal),
+            uint!(0xd164d3531a8e52bcd357897675641b72f543c6ee3688ad7ec32507ffeeae22_U256)
+        );
+    }
+
+    #[test]
+    fn scope_vector() {
+        // Slot 3 on Arbitrum One
+        let scope = scope(U256::from(3), 42161, CONTRACT);
+        assert_eq!(
+            scope,
+            uint!(0xe197b00afc602093b5cd586cb2f9e127936be3b94c0b5ed06010fb3116ca65ef_U256)
+        );
+        assert_eq!(
+            field_hash(scope),
+            uint!(0xcba4c9e9cafbb7018117ed8a9f18d4c9c29140a400363e824809b47bfc6d42_U256)
+        );
+    }
+
+    #[test]
+    fn field_hash_of_zero_is_the_shifted_keccak_of_the_zero_word() {
+        assert_eq!(
+            field_hash(U256::ZERO),
+            uint!(0x290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e5_U256)
+        );
+    }
+
+    #[test]
+    fn field_hashes_fit_the_snark_field() {
+        assert!(field_hash(U256::MAX) < crate::poseidon::FIELD_MODULUS);
+    }
+}
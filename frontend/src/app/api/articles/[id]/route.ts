@@ -1,5 +1,5 @@
 import { NextRequest, NextResponse } from 'next/server';
-import { createPublicClient, createWalletClient, http } from 'viem';
+import { createPublicClient, createWalletClient, http, zeroAddress } from 'viem';
 import { arbitrum } from 'viem/chains';
 import { privateKeyToAccount } from 'viem/accounts';
 
@@ -25,24 +25,48 @@ const WIKIPAY_ABI = [
   {
     type: 'function',
     name: 'nullifiersUsed',
-    inputs: [{ name: 'nullifier', type: 'bytes32' }],
+    inputs: [
+      { name: 'nullifier', type: 'bytes32' },
+      { name: 'articleId', type: 'uint256' },
+      { name: 'payer', type: 'address' }
+    ],
     outputs: [{ name: '', type: 'bool' }],
     stateMutability: 'view'
   },
+  {
+    type: 'function',
+    name: 'getCurrentPrice',
+    inputs: [{ name: 'articleId', type: 'uint256' }],
+    outputs: [{ name: '', type: 'uint256' }],
+    stateMutability: 'view'
+  },
+  {
+    type: 'function',
+    name: 'getVoucherPrice',
+    inputs: [
+      { name: 'articleId', type: 'uint256' },
+      { name: 'code', type: 'string' }
+    ],
+    outputs: [{ name: '', type: 'uint256' }],
+    stateMutability: 'view'
+  },
   {
     type: 'function',
     name: 'unlockArticleX402',
     inputs: [
       { name: 'articleId', type: 'uint256' },
+      { name: 'token', type: 'address' },
       { name: 'nullifier', type: 'bytes32' },
       { name: 'proof', type: 'bytes32' },
       { name: 'from', type: 'address' },
+      { name: 'value', type: 'uint256' },
       { name: 'validAfter', type: 'uint256' },
       { name: 'validBefore', type: 'uint256' },
       { name: 'nonce', type: 'bytes32' },
       { name: 'v', type: 'uint8' },
       { name: 'r', type: 'bytes32' },
-      { name: 's', type: 'bytes32' }
+      { name: 's', type: 'bytes32' },
+      { name: 'voucherCode', type: 'string' }
     ],
     outputs: [{ name: '', type: 'bool' }],
     stateMutability: 'nonpayable'
@@ -60,10 +84,15 @@ const publicClient = createPublicClient({
  * GET /api/articles/:id
  *
  * x402 Flow:
- * 1. Request without payment → HTTP 402 Payment Required
- * 2. Request with X-PAYMENT header → Verify payment via facilitator
+ * 1. Request without payment → HTTP 402 Payment Required, quoting the current price
+ *    (after decay, or after the discount of an optional ?voucher=<code>)
+ * 2. Request with X-PAYMENT header → this server submits the reader's authorization to the
+ *    WikiPay contract on-chain from the BACKEND_PRIVATE_KEY wallet (paying the gas)
  * 3. Return content if payment verified
  *
+ * The reader signs the quoted price as the authorization value, a maximum: the contract
+ * charges the price at settlement, which decay can only lower, and returns the difference
+ *
  * Reference: https://x402.gitbook.io/x402/core-concepts/client-server
  */
 
@@ -72,6 +101,7 @@ interface PaymentPayload {
   nullifier: string;
   proof: string;
   from: string;
+  value: string; // Authorized maximum (the quoted price), in USDC base units
   validAfter: number;
   validBefore: number;
   nonce: string;
@@ -104,10 +134,52 @@ export async function GET(
       args: [articleId]
     });
 
-    const [ipfsHash, preview, price, creator, unlocks, timestamp] = article;
+    const [ipfsHash, preview, publishPrice, creator, unlocks, timestamp] = article;
+
+    // getArticle returns zero values for ids without an article (e.g. gaps left by imports)
+    if (creator === zeroAddress) {
+      console.log('❌ Article not found');
+      console.log('=== x402 Request Failed (Not Found) ===\n');
+      return NextResponse.json(
+        { error: 'Article not found', protocol: 'x402' },
+        { status: 404 }
+      );
+    }
 
     console.log('✅ Article fetched successfully');
     console.log('   IPFS Hash:', ipfsHash);
+    console.log('   Publish price:', publishPrice.toString(), 'USDC tokens');
+
+    // The contract pulls exactly what an unlock costs now, so quote that rather than the
+    // publish price: the decayed price, or the discounted price when a voucher is redeemed
+    const voucherCode = request.nextUrl.searchParams.get('voucher') ?? '';
+    let price: bigint;
+    if (voucherCode) {
+      try {
+        price = await publicClient.readContract({
+          address: WIKIPAY_CONTRACT_ADDRESS,
+          abi: WIKIPAY_ABI,
+          functionName: 'getVoucherPrice',
+          args: [articleId, voucherCode]
+        });
+      } catch (error) {
+        console.error('❌ Voucher rejected:', voucherCode, error);
+        console.log('=== x402 Request Failed (Invalid Voucher) ===\n');
+        return NextResponse.json(
+          { error: 'Invalid voucher', protocol: 'x402', voucher: voucherCode },
+          { status: 400 }
+        );
+      }
+    } else {
+      // RPC failures fall through to the 500 handler below
+      price = await publicClient.readContract({
+        address: WIKIPAY_CONTRACT_ADDRESS,
+        abi: WIKIPAY_ABI,
+        functionName: 'getCurrentPrice',
+        args: [articleId]
+      });
+    }
+
     console.log('   Price:', price.toString(), 'USDC tokens');
     console.log('   Price USD:', (Number(price) / 1_000_000).toFixed(2));
     console.log('   Creator:', creator);
@@ -132,18 +204,19 @@ export async function GET(
             network: 'arbitrum',
             usdc: USDC_ADDRESS,
             articleId: params.id,
-            price: price.toString(),
+            price: price.toString(), // current price, signed as the authorization's maximum value
             priceUSD: (Number(price) / 1_000_000).toFixed(2), // USDC 6 decimals
+            voucher: voucherCode || null,
             creator: creator,
-            facilitator: process.env.NEXT_PUBLIC_FACILITATOR_URL || 'https://facilitator.wikipay.org'
+            payTo: WIKIPAY_CONTRACT_ADDRESS // Payments are escrowed by the contract, not sent to the creator
           },
           preview: preview,
           instructions: {
             description: 'x402 Protocol - Payment Required',
-            step1: 'Create payment payload with USDC authorization (EIP-3009)',
-            step2: 'Sign payment payload with your wallet',
+            step1: 'Create payment payload with USDC receiveWithAuthorization (EIP-3009) to the WikiPay contract',
+            step2: 'Sign payment payload with your wallet (nonce = nullifier key)',
             step3: 'Retry this request with X-PAYMENT header',
-            step4: 'Facilitator will verify and settle payment on blockchain',
+            step4: 'Server submits the authorization to the WikiPay contract on-chain; it charges the price at settlement (at most the signed value) and returns the rest',
             step5: 'Content will be returned upon successful payment verification'
           }
         }),
@@ -158,7 +231,7 @@ export async function GET(
             'X-Payment-ChainId': '42161',
             'X-Payment-Token': 'USDC',
             'X-Payment-Amount': price.toString(),
-            'X-Payment-Recipient': creator
+            'X-Payment-Recipient': WIKIPAY_CONTRACT_ADDRESS
           }
         }
       );
@@ -188,7 +261,7 @@ export async function GET(
 
     // Validate payment payload structure
     console.log('\n✔️ Step 4: Validating payment structure...');
-    const requiredFields = ['articleId', 'nullifier', 'proof', 'from', 'validAfter', 'validBefore', 'nonce', 'v', 'r', 's', 'signature'];
+    const requiredFields = ['articleId', 'nullifier', 'proof', 'from', 'value', 'validAfter', 'validBefore', 'nonce', 'v', 'r', 's', 'signature'];
     const missingFields = requiredFields.filter(field => !(field in paymentPayload));
 
     if (missingFields.length > 0) {
@@ -233,7 +306,7 @@ export async function GET(
       address: WIKIPAY_CONTRACT_ADDRESS,
       abi: WIKIPAY_ABI,
       functionName: 'nullifiersUsed',
-      args: [paymentPayload.nullifier as `0x${string}`]
+      args: [paymentPayload.nullifier as `0x${string}`, articleId, paymentPayload.from as `0x${string}`]
     });
 
     console.log('   Already used:', isUsed);
@@ -252,268 +325,112 @@ export async function GET(
     }
     console.log('✅ Nullifier is fresh (not used)');
 
-    // x402 Step 3: Verify payment with facilitator
-    console.log('\n🌐 Step 7: Preparing facilitator communication...');
-    const facilitatorUrl = process.env.NEXT_PUBLIC_FACILITATOR_URL || process.env.FACILITATOR_URL;
-    console.log('   Facilitator URL:', facilitatorUrl);
+    // x402 Step 3: Settle payment on-chain through the WikiPay contract
+    // The reader signed a USDC receiveWithAuthorization payable to the contract, which only the
+    // contract itself can submit: unlockArticleX402 pulls the payment into escrow and records the unlock
+    console.log('\n🔑 Step 7: Loading facilitator wallet...');
+    const backendPrivateKey = process.env.BACKEND_PRIVATE_KEY?.trim();
 
-    if (!facilitatorUrl) {
-      console.error('❌ Facilitator URL not configured');
-      console.log('=== x402 Request Failed (No Facilitator) ===\n');
+    if (!backendPrivateKey || !backendPrivateKey.startsWith('0x') || backendPrivateKey.length !== 66) {
+      console.error('❌ BACKEND_PRIVATE_KEY missing or invalid - must be 0x followed by 64 hex characters');
+      console.log('=== x402 Request Failed (No Facilitator Wallet) ===\n');
       return NextResponse.json(
         {
           error: 'Facilitator not configured',
           protocol: 'x402',
-          details: 'Server must configure FACILITATOR_URL to process payments'
+          details: 'Server must configure BACKEND_PRIVATE_KEY to settle payments'
         },
         { status: 500 }
       );
     }
 
-    // Step 3a: Verify payment with facilitator (x402 standard format)
-    console.log('\n🔧 Step 8: Building x402 verify payload...');
-
-    // Use the full signature from the payload (already properly formatted by viem)
-    const fullSignature = paymentPayload.signature as string;
-    console.log('   Full signature from payload:', fullSignature.slice(0, 20) + '...' + fullSignature.slice(-4));
-    console.log('   Signature length:', fullSignature.length, 'chars (expected: 132)');
-
-    const x402VerifyPayload = {
-      paymentPayload: {
-        scheme: "exact" as const,
-        network: "arbitrum",
-        x402Version: 1,
-        payload: {
-          signature: fullSignature,
-          authorization: {
-            from: paymentPayload.from,
-            to: creator,
-            value: price.toString(),
-            validAfter: paymentPayload.validAfter,
-            validBefore: paymentPayload.validBefore,
-            nonce: paymentPayload.nonce
-          }
-        }
-      },
-      paymentRequirements: {
-        scheme: "exact" as const,
-        network: "arbitrum",
-        asset: USDC_ADDRESS,
-        payTo: creator,
-        maxAmountRequired: price.toString()
-      }
-    };
-
-    console.log('📤 x402 Verify Payload:');
-    console.log('   scheme:', x402VerifyPayload.paymentPayload.scheme);
-    console.log('   network:', x402VerifyPayload.paymentPayload.network);
-    console.log('   x402Version:', x402VerifyPayload.paymentPayload.x402Version);
-    console.log('   from:', x402VerifyPayload.paymentPayload.payload.authorization.from);
-    console.log('   to:', x402VerifyPayload.paymentPayload.payload.authorization.to);
-    console.log('   value:', x402VerifyPayload.paymentPayload.payload.authorization.value);
-    console.log('   asset:', x402VerifyPayload.paymentRequirements.asset);
-
-    console.log('\n📡 Step 9: Calling facilitator /verify endpoint...');
-    console.log('   URL:', `${facilitatorUrl}/verify`);
-
-    const verifyResponse = await fetch(`${facilitatorUrl}/verify`, {
-      method: 'POST',
-      headers: {
-        'Content-Type': 'application/json',
-        'X-Protocol': 'x402'
-      },
-      body: JSON.stringify(x402VerifyPayload)
+    const account = privateKeyToAccount(backendPrivateKey as `0x${string}`);
+    const walletClient = createWalletClient({
+      account,
+      chain: arbitrum,
+      transport: http()
+    });
+    console.log('   Facilitator wallet:', account.address);
+
+    const unlockArgs = [
+      BigInt(params.id), // articleId
+      USDC_ADDRESS, // token
+      paymentPayload.nullifier as `0x${string}`, // nullifier
+      paymentPayload.proof as `0x${string}`, // proof
+      paymentPayload.from as `0x${string}`, // from (user who signed)
+      BigInt(paymentPayload.value), // value (signed maximum; excess over the price is returned)
+      BigInt(paymentPayload.validAfter), // validAfter
+      BigInt(paymentPayload.validBefore), // validBefore
+      paymentPayload.nonce as `0x${string}`, // nonce (nullifier key)
+      paymentPayload.v, // v
+      paymentPayload.r as `0x${string}`, // r
+      paymentPayload.s as `0x${string}`, // s
+      voucherCode // voucherCode (same ?voucher= the price was quoted with)
+    ] as const;
+
+    // Simulate first: an invalid authorization or used nullifier is rejected without spending gas
+    console.log('\n🧪 Step 8: Simulating unlockArticleX402...');
+    let simulationError: any = null;
+    const simulation = await publicClient.simulateContract({
+      account,
+      address: WIKIPAY_CONTRACT_ADDRESS,
+      abi: WIKIPAY_ABI,
+      functionName: 'unlockArticleX402',
+      args: unlockArgs
+    }).catch((error) => {
+      simulationError = error;
+      return null;
     });
 
-    console.log('📥 Verify response status:', verifyResponse.status);
-
-    if (!verifyResponse.ok) {
-      console.error('❌ Facilitator verification failed');
-      const error = await verifyResponse.json().catch(() => ({ message: 'Facilitator verification error' }));
-      console.error('   Error:', error);
+    if (!simulation) {
+      const reason = simulationError?.shortMessage || simulationError?.message || 'Contract rejected payment';
+      console.error('❌ Payment verification failed:', reason);
       console.log('=== x402 Request Failed (Verification) ===\n');
       return NextResponse.json(
         {
           error: 'Payment verification failed',
           protocol: 'x402',
-          details: error.message || 'Facilitator rejected payment'
+          details: reason
         },
         { status: 402 }
       );
     }
+    console.log('✅ Payment verified (simulation succeeded)');
 
-    const verifyResult = await verifyResponse.json();
-    console.log('✅ Verify response received');
-    console.log('   isValid:', verifyResult.isValid);
-    console.log('   payer:', verifyResult.payer);
+    console.log('\n📝 Step 9: Settling payment on-chain...');
+    console.log('   Contract:', WIKIPAY_CONTRACT_ADDRESS);
+    console.log('   Nullifier:', paymentPayload.nullifier);
 
-    // x402 standard verification response: { isValid: boolean, payer: string, invalidReason?: string }
-    if (!verifyResult.isValid) {
-      console.error('❌ Payment verification invalid');
-      console.error('   Reason:', verifyResult.invalidReason);
-      console.log('=== x402 Request Failed (Invalid Payment) ===\n');
-      return NextResponse.json(
-        {
-          error: 'Payment verification failed',
-          protocol: 'x402',
-          details: verifyResult.invalidReason || 'Verification failed'
-        },
-        { status: 402 }
-      );
-    }
+    let txHash: `0x${string}`;
+    let blockNumber: bigint;
+    try {
+      txHash = await walletClient.writeContract(simulation.request);
+      console.log('✅ Contract call submitted:', txHash);
 
-    console.log('✅ Payment verified successfully');
-
-    // Step 3b: Settle payment with facilitator (x402 standard format)
-    console.log('\n🔧 Step 10: Building x402 settle payload...');
-    // Reuse the same signature we constructed for verification
-    const x402SettlePayload = {
-      paymentPayload: {
-        scheme: "exact" as const,
-        network: "arbitrum",
-        x402Version: 1,
-        payload: {
-          signature: fullSignature,
-          authorization: {
-            from: paymentPayload.from,
-            to: creator,
-            value: price.toString(),
-            validAfter: paymentPayload.validAfter,
-            validBefore: paymentPayload.validBefore,
-            nonce: paymentPayload.nonce
-          }
-        }
-      },
-      paymentRequirements: {
-        scheme: "exact" as const,
-        network: "arbitrum",
-        asset: USDC_ADDRESS,
-        payTo: creator,
-        maxAmountRequired: price.toString()
+      const receipt = await publicClient.waitForTransactionReceipt({ hash: txHash });
+      if (receipt.status !== 'success') {
+        throw new Error(`Transaction ${txHash} reverted`);
       }
-    };
-
-    console.log('📤 x402 Settle Payload (same as verify)');
-
-    console.log('\n📡 Step 11: Calling facilitator /settle endpoint...');
-    console.log('   URL:', `${facilitatorUrl}/settle`);
-
-    const settleResponse = await fetch(`${facilitatorUrl}/settle`, {
-      method: 'POST',
-      headers: {
-        'Content-Type': 'application/json',
-        'X-Protocol': 'x402'
-      },
-      body: JSON.stringify(x402SettlePayload)
-    });
-
-    console.log('📥 Settle response status:', settleResponse.status);
-
-    if (!settleResponse.ok) {
-      console.error('❌ Facilitator settlement failed');
-      const error = await settleResponse.json().catch(() => ({ message: 'Facilitator settlement error' }));
-      console.error('   Error:', error);
+      blockNumber = receipt.blockNumber;
+      console.log('✅ Transaction confirmed at block:', receipt.blockNumber);
+      console.log('   Gas used:', receipt.gasUsed);
+    } catch (error: any) {
+      console.error('❌ Settlement failed:', error.shortMessage || error.message);
       console.log('=== x402 Request Failed (Settlement) ===\n');
       return NextResponse.json(
         {
           error: 'Payment settlement failed',
           protocol: 'x402',
-          details: error.message || 'Facilitator could not settle payment'
+          details: error.shortMessage || error.message || 'Could not settle payment'
         },
         { status: 402 }
       );
     }
 
-    const facilitatorResult = await settleResponse.json();
-    console.log('✅ Settle response received');
-    console.log('   success:', facilitatorResult.success);
-    console.log('   transaction:', facilitatorResult.transaction);
-    console.log('   network:', facilitatorResult.network);
-    console.log('   payer:', facilitatorResult.payer);
-
-    // x402 standard settlement response: { success: boolean, transaction: string, network: string, payer: string, errorReason?: string }
-    if (!facilitatorResult.success) {
-      console.error('❌ Settlement failed');
-      console.error('   Error reason:', facilitatorResult.errorReason);
-      console.log('=== x402 Request Failed (Settlement Failed) ===\n');
-      return NextResponse.json(
-        {
-          error: 'Payment settlement failed',
-          protocol: 'x402',
-          details: facilitatorResult.errorReason || 'Unknown error'
-        },
-        { status: 402 }
-      );
-    }
-
-    console.log('✅ Payment settled successfully');
-
-    // x402 Step 4a: Call WikiPay contract to mark nullifier as used
-    console.log('\n📝 Step 12: Marking nullifier as used on-chain...');
-    console.log('   Contract:', WIKIPAY_CONTRACT_ADDRESS);
-    console.log('   Nullifier:', paymentPayload.nullifier);
-    console.log('   Article ID:', params.id);
-    console.log('   Proof:', paymentPayload.proof);
-
-    // Get backend wallet from environment
-    const backendPrivateKey = process.env.BACKEND_PRIVATE_KEY?.trim();
-
-    if (!backendPrivateKey) {
-      console.warn('⚠️ BACKEND_PRIVATE_KEY not set - skipping on-chain nullifier marking');
-      console.warn('   Users will be able to pay again if they reload the page!');
-    } else if (!backendPrivateKey.startsWith('0x') || backendPrivateKey.length !== 66) {
-      console.error('❌ Invalid BACKEND_PRIVATE_KEY format - must be 0x followed by 64 hex characters');
-      console.error(`   Current length: ${backendPrivateKey.length}, starts with 0x: ${backendPrivateKey.startsWith('0x')}`);
-    } else {
-      try {
-        // Create wallet client with backend wallet
-        const account = privateKeyToAccount(backendPrivateKey as `0x${string}`);
-        const walletClient = createWalletClient({
-          account,
-          chain: arbitrum,
-          transport: http()
-        });
-
-        console.log('   Backend wallet:', account.address);
-
-        // Call unlockArticleX402 (payment already done via USDC by facilitator)
-        const txHash = await walletClient.writeContract({
-          address: WIKIPAY_CONTRACT_ADDRESS,
-          abi: WIKIPAY_ABI,
-          functionName: 'unlockArticleX402',
-          args: [
-            BigInt(params.id), // articleId
-            paymentPayload.nullifier as `0x${string}`, // nullifier
-            paymentPayload.proof as `0x${string}`, // proof
-            paymentPayload.from as `0x${string}`, // from (user who signed)
-            BigInt(paymentPayload.validAfter), // validAfter
-            BigInt(paymentPayload.validBefore), // validBefore
-            paymentPayload.nonce as `0x${string}`, // nonce
-            paymentPayload.v, // v
-            paymentPayload.r as `0x${string}`, // r
-            paymentPayload.s as `0x${string}` // s
-          ]
-        });
-
-        console.log('✅ Contract call submitted:', txHash);
-
-        // Wait for transaction confirmation
-        const receipt = await publicClient.waitForTransactionReceipt({ hash: txHash });
-        console.log('✅ Transaction confirmed at block:', receipt.blockNumber);
-        console.log('   Gas used:', receipt.gasUsed);
-
-      } catch (error: any) {
-        console.error('❌ Failed to mark nullifier on-chain:', error.message);
-        console.error('   Settlement was successful, but on-chain marking failed');
-        console.error('   User may be able to unlock again if page is reloaded');
-        // Don't fail the entire request - settlement was successful
-      }
-    }
-
     // x402 Step 4b: Payment verified and settled → Return content
-    console.log('\n📦 Step 13: Preparing content delivery...');
+    console.log('\n📦 Step 10: Preparing content delivery...');
     console.log('   IPFS Hash:', ipfsHash);
-    console.log('   Transaction:', facilitatorResult.transaction);
+    console.log('   Transaction:', txHash);
 
     console.log('✅ Returning HTTP 200 with content');
     console.log('=== x402 Request Complete (Success) ===\n');
@@ -539,13 +456,13 @@ export async function GET(
         },
         payment: {
           verified: true,
-          transactionHash: facilitatorResult.transaction,
-          blockNumber: null, // x402 standard doesn't return blockNumber
+          transactionHash: txHash,
+          blockNumber: blockNumber.toString(),
           nullifier: paymentPayload.nullifier,
           paidAmount: price.toString(),
           paidAmountUSD: (Number(price) / 1_000_000).toFixed(2),
-          network: facilitatorResult.network,
-          payer: facilitatorResult.payer
+          network: 'arbitrum',
+          payer: paymentPayload.from
         }
       },
       {
@@ -554,8 +471,8 @@ export async function GET(
           'Content-Type': 'application/json',
           'X-Protocol': 'x402',
           'X-Payment-Verified': 'true',
-          'X-Transaction-Hash': facilitatorResult.transactionHash || '',
-          'X-Block-Number': facilitatorResult.blockNumber?.toString() || ''
+          'X-Transaction-Hash': txHash,
+          'X-Block-Number': blockNumber.toString()
         }
       }
     );
@@ -16,10 +16,10 @@ import {
   DialogTitle,
 } from "@/components/ui/dialog";
 import { ConnectButton } from "@rainbow-me/rainbowkit";
-import { getArticle, checkIfUnlocked, generateZkProof, generateTransferAuthorization, getWalletClient } from "@/lib/contract";
+import { getArticle, checkIfUnlocked, generateZkProof, generateReceiveAuthorization, getUnlockNonce, getWalletClient } from "@/lib/contract";
 import { simpleDecrypt } from "@/lib/encryption";
 import { useAccount } from "wagmi";
-import { formatEther, keccak256, toBytes } from "viem";
+import { formatEther } from "viem";
 
 export default function ArticlePage() {
   const params = useParams();
@@ -151,14 +151,15 @@ export default function ArticlePage() {
       const validAfter = BigInt(Math.floor(Date.now() / 1000));
       const validBefore = validAfter + BigInt(3600); // 1 hour validity
 
-      // Generate unique nonce (nullifier + timestamp) to prevent reuse
-      const timestamp = BigInt(Math.floor(Date.now() / 1000));
-      const nonceData = `${nullifier}${timestamp.toString()}`;
-      const nonce = keccak256(toBytes(nonceData)) as `0x${string}`;
+      // Nonce is derived from the nullifier key, so the authorization only unlocks this article for this wallet
+      const nonce = await getUnlockNonce(nullifier, BigInt(articleId), account);
 
-      const { v, r, s, signature } = await generateTransferAuthorization(
+      // Payee is the WikiPay contract, which holds the payment in escrow for the creator.
+      // The quoted price is signed as a maximum: the contract charges the price at settlement,
+      // which decay can only lower, and returns the difference
+      const { v, r, s, signature } = await generateReceiveAuthorization(
         account,
-        paymentDetails.payment.creator as `0x${string}`,
+        paymentDetails.payment.contract as `0x${string}`,
         BigInt(paymentDetails.payment.price),
         validAfter,
         validBefore,
@@ -172,6 +173,7 @@ export default function ArticlePage() {
         nullifier,
         proof,
         from: account,
+        value: paymentDetails.payment.price,
         validAfter: Number(validAfter),
         validBefore: Number(validBefore),
         nonce,
@@ -180,8 +182,8 @@ export default function ArticlePage() {
       };
 
       // x402 Step 3: Retry request with X-PAYMENT header
-      setPaymentStep(`🚀 Sending to x402 facilitator (${process.env.NEXT_PUBLIC_FACILITATOR_URL})...`);
-      console.log("🚀 Sending payment to facilitator...");
+      setPaymentStep("🚀 Sending signed authorization to the WikiPay server for on-chain settlement...");
+      console.log("🚀 Sending payment to the server...");
 
       const paymentResponse = await fetch(`/api/articles/${articleId}`, {
         method: 'GET',
@@ -197,10 +199,10 @@ export default function ArticlePage() {
       }
 
       // x402 Step 4: Content delivered after payment verification
-      setPaymentStep("✅ Facilitator verified payment! Submitting to blockchain...");
+      setPaymentStep("✅ Payment settled on-chain by the WikiPay contract!");
       const data = await paymentResponse.json();
 
-      console.log("✅ Payment verified by facilitator!");
+      console.log("✅ Payment settled on-chain!");
       console.log("Transaction:", data.payment.transactionHash);
 
       // Decrypt content from IPFS
@@ -231,8 +233,7 @@ export default function ArticlePage() {
       setUnlockResult({
         transactionHash: data.payment.transactionHash,
         blockNumber: data.payment.blockNumber,
-        gasUsed: 0, // Facilitator paid gas, not user
-        facilitatorUrl: process.env.NEXT_PUBLIC_FACILITATOR_URL || 'facilitator',
+        gasUsed: 0, // The server's settlement wallet paid gas, not the user
         paidAmount: data.payment.paidAmountUSD
       });
       setFullContent(content);
@@ -315,7 +316,7 @@ export default function ArticlePage() {
                 <div className="flex justify-between items-start">
                   <CardTitle className="text-2xl">Preview</CardTitle>
                   <Badge variant="secondary" className="text-lg px-4 py-2">
-                    {formatUSDC(article.price)} USDC
+                    {formatUSDC(article.currentPrice)} USDC
                   </Badge>
                 </div>
               </CardHeader>
@@ -373,7 +374,10 @@ export default function ArticlePage() {
                     <div className="bg-gradient-to-br from-purple-50 to-blue-50 dark:from-purple-950/30 dark:to-blue-950/30 p-6 rounded-lg">
                       <h3 className="text-xl font-bold mb-2">🔒 Full Article Locked</h3>
                       <p className="text-slate-600 dark:text-slate-400 mb-4">
-                        Unlock the full content anonymously for {formatUSDC(article.price)} USDC (gasless)
+                        Unlock the full content anonymously for {formatUSDC(article.currentPrice)} USDC (gasless)
+                      </p>
+                      <p className="text-xs text-slate-500 dark:text-slate-400 mb-4">
+                        You sign this price as a maximum. WikiPay settles on-chain and charges the price at that moment; if it has decayed since, the difference is returned to your wallet
                       </p>
                       {!isConnected ? (
                         <div>
@@ -389,7 +393,7 @@ export default function ArticlePage() {
                           disabled={unlocking}
                           className="w-full bg-gradient-to-r from-blue-600 to-purple-600 hover:from-blue-700 hover:to-purple-700"
                         >
-                          {unlocking ? "🔓 Processing x402 Payment..." : `🔓 Unlock for ${formatUSDC(article.price)} USDC (No Gas Fees)`}
+                          {unlocking ? "🔓 Processing x402 Payment..." : `🔓 Unlock for ${formatUSDC(article.currentPrice)} USDC (No Gas Fees)`}
                         </Button>
                       )}
                     </div>
@@ -459,7 +463,7 @@ export default function ArticlePage() {
                   </div>
                   <div className="flex justify-between items-center">
                     <span className="text-slate-600 dark:text-slate-400">Gas Paid By:</span>
-                    <span className="font-semibold text-green-600">x402 Facilitator ✓</span>
+                    <span className="font-semibold text-green-600">WikiPay server ✓</span>
                   </div>
                 </div>
               )}
@@ -471,7 +475,8 @@ export default function ArticlePage() {
                 <p className="text-slate-600 dark:text-slate-400">✓ Zero-knowledge proof verified on-chain</p>
                 <p className="text-slate-600 dark:text-slate-400">✓ Payment processed anonymously via nullifier</p>
                 <p className="text-slate-600 dark:text-slate-400">✓ Gasless USDC payment (EIP-3009)</p>
-                <p className="text-slate-600 dark:text-slate-400">✓ Facilitator handled blockchain submission</p>
+                <p className="text-slate-600 dark:text-slate-400">✓ WikiPay server settled the payment on-chain (receiveWithAuthorization)</p>
+                <p className="text-slate-600 dark:text-slate-400">✓ Charged the price at settlement, never more than you signed</p>
               </div>
             </div>
 
@@ -6,6 +6,7 @@ import { Button } from "@/components/ui/button";
 import { Card, CardContent, CardDescription, CardHeader, CardTitle } from "@/components/ui/card";
 import { Badge } from "@/components/ui/badge";
 import { ConnectButton } from "@rainbow-me/rainbowkit";
+import { zeroAddress } from "viem";
 import { getTotalArticles, getArticle } from "@/lib/contract";
 
 interface Article {
@@ -49,14 +50,17 @@ export default function ArticlesPage() {
           getArticle(i).then((article) => ({
             id: Number(i),
             creator: article.creator,
-            price: article.price,
+            price: article.currentPrice,
             unlocks: article.unlocks,
             preview: article.preview
           }))
         );
       }
 
-      const loadedArticles = await Promise.all(articlePromises);
+      // Imports with an id offset leave ids without an article (creator address(0))
+      const loadedArticles = (await Promise.all(articlePromises)).filter(
+        (article) => article.creator !== zeroAddress
+      );
       console.log("✅ Loaded", loadedArticles.length, "articles");
       console.log("Articles:", loadedArticles);
 
@@ -1,4 +1,4 @@
-import { createPublicClient, createWalletClient, custom, http, parseUnits, keccak256, toBytes } from 'viem';
+import { createPublicClient, createWalletClient, custom, http, parseUnits, zeroAddress } from 'viem';
 import { arbitrum } from 'viem/chains';
 import WikiPayX402ABI from './WikiPayX402-ABI.json';
 
@@ -13,7 +13,7 @@ export const USDC_DECIMALS = 6;
 export const USDC_ABI = [
   {
     type: 'function',
-    name: 'transferWithAuthorization',
+    name: 'receiveWithAuthorization',
     inputs: [
       { name: 'from', type: 'address' },
       { name: 'to', type: 'address' },
@@ -155,6 +155,16 @@ export async function getTotalArticles(): Promise<bigint> {
   return result as bigint;
 }
 
+// Helper: Price an unlock charges now (publish price after the article's decay schedule)
+export async function getCurrentPrice(articleId: bigint): Promise<bigint> {
+  return await publicClient.readContract({
+    address: WIKIPAY_CONTRACT_ADDRESS,
+    abi: WIKIPAY_ABI,
+    functionName: 'getCurrentPrice',
+    args: [articleId]
+  }) as bigint;
+}
+
 // Helper: Get article details
 export async function getArticle(articleId: bigint) {
   const result = await publicClient.readContract({
@@ -168,10 +178,15 @@ export async function getArticle(articleId: bigint) {
   // Viem automatically converts this to an array
   const [ipfsHash, preview, price, creator, unlocks, timestamp] = result as [string, string, bigint, `0x${string}`, bigint, bigint];
 
+  // `price` is the publish price; unlocks charge the decayed current price.
+  // Ids left empty by imports have no creator, and getCurrentPrice reverts for them
+  const currentPrice = creator === zeroAddress ? price : await getCurrentPrice(articleId);
+
   return {
     ipfsHash,
     preview,
     price,
+    currentPrice,
     creator,
     unlocks,
     timestamp
@@ -226,12 +241,12 @@ export async function checkIfUnlocked(articleId: bigint): Promise<boolean> {
     // Generate the nullifier this wallet would use
     const nullifier = await generateDeterministicNullifier(account, articleId);
 
-    // Check if nullifier is used on-chain (WikiPay contract)
+    // Check if this wallet used the nullifier for this article on-chain (WikiPay contract)
     const isUsed = await publicClient.readContract({
       address: WIKIPAY_CONTRACT_ADDRESS,
       abi: WIKIPAY_ABI,
       functionName: 'nullifiersUsed',
-      args: [nullifier as `0x${string}`]
+      args: [nullifier as `0x${string}`, articleId, account]
     });
 
     console.log(`✅ Nullifier check for article ${articleId}:`, isUsed);
@@ -243,8 +258,25 @@ export async function checkIfUnlocked(articleId: bigint): Promise<boolean> {
   }
 }
 
-// Helper: Generate EIP-3009 transfer authorization signature
-export async function generateTransferAuthorization(
+// Helper: EIP-3009 nonce the contract expects for this unlock
+// The nullifier key (bound to the article and the paying wallet), re-derived by the contract
+// after each refund so the reader can pay again
+export async function getUnlockNonce(
+  nullifier: `0x${string}`,
+  articleId: bigint,
+  payer: `0x${string}`
+): Promise<`0x${string}`> {
+  return await publicClient.readContract({
+    address: WIKIPAY_CONTRACT_ADDRESS,
+    abi: WIKIPAY_ABI,
+    functionName: 'getUnlockNonce',
+    args: [nullifier, articleId, payer]
+  }) as `0x${string}`;
+}
+
+// Helper: Generate EIP-3009 receive authorization signature
+// The payee (`to`) must be the WikiPay contract: only it can submit receiveWithAuthorization
+export async function generateReceiveAuthorization(
   from: `0x${string}`,
   to: `0x${string}`,
   value: bigint,
@@ -255,7 +287,7 @@ export async function generateTransferAuthorization(
   const walletClient = await getWalletClient();
 
   // EIP-712 domain for Circle USDC on Arbitrum One
-  // IMPORTANT: Must match the actual USDC contract's EIP-3009 domain for transferWithAuthorization
+  // IMPORTANT: Must match the actual USDC contract's EIP-3009 domain for receiveWithAuthorization
   // Circle's USDC uses "USD Coin" (not "USDC") for the domain name
   // See: https://github.com/circlefin/stablecoin-evm
   const domain = {
@@ -265,9 +297,9 @@ export async function generateTransferAuthorization(
     verifyingContract: USDC_ADDRESS
   } as const;
 
-  // EIP-712 types for transferWithAuthorization
+  // EIP-712 types for receiveWithAuthorization
   const types = {
-    TransferWithAuthorization: [
+    ReceiveWithAuthorization: [
       { name: 'from', type: 'address' },
       { name: 'to', type: 'address' },
       { name: 'value', type: 'uint256' },
@@ -291,7 +323,7 @@ export async function generateTransferAuthorization(
     account: from,
     domain,
     types,
-    primaryType: 'TransferWithAuthorization',
+    primaryType: 'ReceiveWithAuthorization',
     message
   });
 
@@ -304,30 +336,26 @@ export async function generateTransferAuthorization(
 }
 
 // Helper: Unlock article with USDC using EIP-3009 (x402 protocol)
-export async function unlockArticle(articleId: bigint, price: bigint) {
+export async function unlockArticle(articleId: bigint) {
   console.log("🔓 Unlocking article with USDC (x402 protocol)...");
   console.log("Article ID:", articleId);
+
+  // The authorization signs the current price as a maximum: the contract charges the price
+  // at settlement, which decay can only lower, and returns the difference
+  const price = await getCurrentPrice(articleId);
   console.log("Price (USDC tokens):", price.toString());
 
   const walletClient = await getWalletClient();
   const [account] = await walletClient.getAddresses();
 
-  // Get article creator address
-  const article = await getArticle(articleId);
-  const creator = article.creator as `0x${string}`;
-
   // Generate deterministic ZK proof and nullifier
   const { nullifier, proof } = await generateZkProof(articleId);
 
   console.log("Generated nullifier:", nullifier);
   console.log("Generated proof:", proof);
 
-  // Generate unique nonce for EIP-3009
-  // IMPORTANT: Nonce must be unique for each authorization attempt
-  // We use nullifier + timestamp to ensure uniqueness while maintaining some determinism
-  const timestamp = BigInt(Math.floor(Date.now() / 1000));
-  const nonceData = `${nullifier}${timestamp.toString()}`;
-  const nonce = keccak256(toBytes(nonceData)) as `0x${string}`;
+  // EIP-3009 nonce: derived from the nullifier key, so the signature commits to this article, wallet and contract
+  const nonce = await getUnlockNonce(nullifier, articleId, account);
 
   console.log("Generated EIP-3009 nonce:", nonce);
 
@@ -337,10 +365,10 @@ export async function unlockArticle(articleId: bigint, price: bigint) {
 
   console.log("Generating EIP-3009 authorization...");
 
-  // Generate EIP-3009 transfer authorization signature
-  const { v, r, s, signature } = await generateTransferAuthorization(
+  // Generate EIP-3009 receive authorization signature (payment goes to the contract's escrow)
+  const { v, r, s, signature } = await generateReceiveAuthorization(
     account,
-    creator,
+    WIKIPAY_CONTRACT_ADDRESS,
     price,
     validAfter,
     validBefore,
@@ -351,12 +379,12 @@ export async function unlockArticle(articleId: bigint, price: bigint) {
   console.log("Full signature:", signature);
 
   try {
-    // Call unlockArticleX402 with EIP-3009 authorization
+    // Call unlockArticleX402 with EIP-3009 authorization (signed value = maximum price, no voucher)
     const hash = await walletClient.writeContract({
       address: WIKIPAY_CONTRACT_ADDRESS,
       abi: WIKIPAY_ABI,
       functionName: 'unlockArticleX402',
-      args: [articleId, nullifier, proof, account, validAfter, validBefore, nonce, v, r, s],
+      args: [articleId, USDC_ADDRESS, nullifier, proof, account, price, validAfter, validBefore, nonce, v, r, s, ''],
       account
     });
 